@@ -0,0 +1,107 @@
+use std::fmt;
+
+// The answer to one part of a day. Every puzzle so far has a numeric answer,
+// but some puzzles have a word or a code as the answer so we allow text too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i32, i64, i128, isize, u32, u64, usize);
+
+// A u128 might not fit in an i128, in which case we fall back to the text of
+// the number rather than getting the wrong answer
+impl From<u128> for Answer {
+    fn from(n: u128) -> Answer {
+        match i128::try_from(n) {
+            Ok(n) => Answer::Number(n),
+            Err(_) => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+// A value worked out on the way to an answer, which is either a single value
+// or a list of them (like the three largest basins in day 9)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Detail {
+    Single(Answer),
+    List(Vec::<Answer>),
+}
+
+impl<T: Into<Answer>> From<T> for Detail {
+    fn from(value: T) -> Detail {
+        Detail::Single(value.into())
+    }
+}
+
+impl fmt::Display for Detail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Detail::Single(answer) => write!(f, "{}", answer),
+            Detail::List(answers) => {
+                let answers: Vec::<String> = answers.iter().map(|a| a.to_string()).collect();
+                write!(f, "[{}]", answers.join(", "))
+            },
+        }
+    }
+}
+
+// The named details of a part, in the order they were worked out
+pub type Details = Vec::<(&'static str, Detail)>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_of_any_type_are_equal() {
+        assert_eq!(Answer::from(5_u32), Answer::from(5_usize));
+        assert_eq!(Answer::from(-3_i32).to_string(), "-3");
+    }
+
+    #[test]
+    fn huge_u128_falls_back_to_text() {
+        assert_eq!(Answer::from(u128::MAX), Answer::Text(u128::MAX.to_string()));
+        assert_eq!(Answer::from(7_u128), Answer::Number(7));
+    }
+
+    #[test]
+    fn details_display_lists() {
+        assert_eq!(Detail::from(3_u32).to_string(), "3");
+        assert_eq!(Detail::List(vec![Answer::from(95_usize), Answer::from(94_usize)]).to_string(), "[95, 94]");
+    }
+}
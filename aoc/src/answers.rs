@@ -0,0 +1,207 @@
+// The registry of known answers kept in answers.toml, e.g.
+//
+//     [day01]
+//     "ex01/input.txt" = { part1 = 1553, part2 = 1597 }
+//
+// We only need a small part of TOML for this (tables of inline tables of
+// numbers and strings), so rather than pull in a dependency we parse just
+// that here.
+use crate::{Answer, ParseError, Part};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredAnswer {
+    pub day: u8,
+    pub part: Part,
+    // The path of the input, relative to the answers file
+    pub input: String,
+    pub expected: Answer,
+}
+
+pub fn parse_answers(text: &str) -> Result<Vec::<RegisteredAnswer>, ParseError> {
+    let mut answers = Vec::new();
+    let mut day: Option<u8> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let mut cursor = Cursor { line_index: i, line, pos: 0 };
+        cursor.skip_whitespace();
+
+        if cursor.at_end() {
+            continue;
+        }
+
+        if cursor.eat('[') {
+            day = Some(cursor.day_header()?);
+            cursor.expect(']')?;
+        } else {
+            let current_day = day.ok_or_else(|| cursor.error("a [dayNN] header before any answers"))?;
+
+            let input = cursor.string()?;
+            cursor.expect('=')?;
+            cursor.expect('{')?;
+
+            loop {
+                let part = cursor.part_key()?;
+                cursor.expect('=')?;
+                let expected = cursor.value()?;
+
+                answers.push(RegisteredAnswer { day: current_day, part, input: input.clone(), expected });
+
+                if !cursor.eat(',') {
+                    break;
+                }
+            }
+
+            cursor.expect('}')?;
+        }
+
+        cursor.skip_whitespace();
+        if !cursor.at_end() {
+            return Err(cursor.error("the end of the line"))
+        }
+    }
+
+    Ok(answers)
+}
+
+// Walks along a single line of the file, keeping track of where we are so
+// that errors can point at the right column
+struct Cursor<'a> {
+    line_index: usize,
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    // Comments run to the end of the line so count as the end too
+    fn at_end(&self) -> bool {
+        self.rest().is_empty() || self.rest().starts_with('#')
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.line.len() - self.rest().trim_start().len();
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(self.line_index, self.line, self.rest(), expected)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            return true
+        }
+
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("{:?}", c)))
+        }
+    }
+
+    // Takes characters while they match, returning what was taken
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+
+        &rest[..len]
+    }
+
+    fn day_header(&mut self) -> Result<u8, ParseError> {
+        let name = self.take_while(|c| c.is_ascii_alphanumeric());
+
+        name.strip_prefix("day")
+            .and_then(|day| day.parse().ok())
+            .ok_or_else(|| ParseError::new(self.line_index, self.line, name, "a table name like day01"))
+    }
+
+    fn part_key(&mut self) -> Result<Part, ParseError> {
+        let key = self.take_while(|c| c.is_ascii_alphanumeric());
+
+        match key {
+            "part1" => Ok(Part::One),
+            "part2" => Ok(Part::Two),
+            _ => Err(ParseError::new(self.line_index, self.line, key, "part1 or part2")),
+        }
+    }
+
+    // A double quoted string, we don't need to support any escapes
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+
+        match self.rest().find('"') {
+            Some(len) => {
+                let string = &self.rest()[..len];
+                self.pos += len + 1;
+                Ok(string.to_string())
+            },
+            None => Err(self.error("a closing '\"'")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Answer, ParseError> {
+        self.skip_whitespace();
+        if self.rest().starts_with('"') {
+            return Ok(Answer::Text(self.string()?))
+        }
+
+        let number = self.take_while(|c| c.is_ascii_digit() || c == '-' || c == '_');
+        number
+            .replace('_', "")
+            .parse()
+            .map(Answer::Number)
+            .map_err(|_| ParseError::new(self.line_index, self.line, number, "a number or a string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tables_of_answers() {
+        let text = "\
+# a comment
+[day01]
+\"ex01/input.txt\" = { part1 = 1553, part2 = \"abc\" }  # trailing comment
+
+[day11]
+\"ex11/test_input.txt\" = { part2 = 195 }
+";
+        let answers = parse_answers(text).unwrap();
+
+        assert_eq!(answers, vec![
+            RegisteredAnswer {
+                day: 1, part: Part::One, input: String::from("ex01/input.txt"), expected: Answer::Number(1553)
+            },
+            RegisteredAnswer {
+                day: 1, part: Part::Two, input: String::from("ex01/input.txt"), expected: Answer::from("abc")
+            },
+            RegisteredAnswer {
+                day: 11, part: Part::Two, input: String::from("ex11/test_input.txt"), expected: Answer::Number(195)
+            },
+        ]);
+    }
+
+    #[test]
+    fn answers_need_a_day() {
+        let error = parse_answers("\"ex01/input.txt\" = { part1 = 1 }").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn bad_part_is_reported() {
+        let error = parse_answers("[day01]\n\"input.txt\" = { part3 = 1 }").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 17, "part3"));
+    }
+}
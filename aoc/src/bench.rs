@@ -0,0 +1,84 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{ParseError, Solution};
+
+// How long each phase of a day took on every iteration of a benchmark
+pub struct Timings {
+    pub parse: Vec::<Duration>,
+    pub part1: Vec::<Duration>,
+    pub part2: Vec::<Duration>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+// Times parsing and both parts separately, so that a slow parser doesn't hide
+// in the time for the parts (or the other way round)
+pub fn bench<S: Solution>(input: &str, iterations: usize) -> Result<Timings, ParseError> {
+    let mut timings = Timings {
+        parse: Vec::with_capacity(iterations),
+        part1: Vec::with_capacity(iterations),
+        part2: Vec::with_capacity(iterations),
+    };
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let parsed = S::parse(black_box(input))?;
+        timings.parse.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part1(&parsed));
+        timings.part1.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part2(&parsed));
+        timings.part2.push(start.elapsed());
+    }
+
+    Ok(timings)
+}
+
+// Returns None if there aren't any durations to summarise
+pub fn summarise(durations: &[Duration]) -> Option<Summary> {
+    let mut sorted = durations.to_vec();
+    sorted.sort();
+
+    // nearest rank percentile, so p95 of 20 samples is the 19th
+    let p95_index = (sorted.len() * 95).div_ceil(100).checked_sub(1)?;
+
+    Some(Summary {
+        min: *sorted.first()?,
+        median: sorted[sorted.len() / 2],
+        p95: sorted[p95_index],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec::<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn summary_of_unsorted_durations() {
+        let durations = millis(&[5, 1, 4, 2, 3, 20, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
+
+        assert_eq!(summarise(&durations), Some(Summary {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(11),
+            p95: Duration::from_millis(19),
+        }));
+    }
+
+    #[test]
+    fn summary_of_nothing() {
+        assert_eq!(summarise(&[]), None);
+    }
+}
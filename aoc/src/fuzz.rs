@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{solve, PartResult, Rng, Solution};
+
+// A small mutation based fuzzer for the days' parsers and solutions. Inputs
+// from a corpus (seeded from each day's test_input.txt) are mutated over and
+// over, and every mutated input goes through the same parse and solve as the
+// runner. Nothing should ever panic: a malformed input is a ParseError, and an
+// input without an answer is None.
+//
+// The mutations know nothing about the puzzles, but they're biased towards the
+// bytes the puzzle inputs are made of, so that a good share of the mutated
+// inputs still get past the parsers and into the solutions.
+
+// Bytes that mean something in at least one of the days' inputs
+const INTERESTING_BYTES: &[u8] = b"0123456789,\n -> |abcdefg()[]{}<>\r\t";
+
+// Numbers that are likely to find overflows and off by one errors
+const INTERESTING_NUMBERS: &[&str] = &[
+    "0", "1", "9", "10", "255", "256", "999", "1000", "9999", "65535", "2147483647",
+    "2147483648", "4294967295", "4294967296", "18446744073709551615", "-1",
+];
+
+// Characters which are more than one byte long, to catch slicing strings in
+// the middle of a character
+const MULTIBYTE: &[&str] = &["é", "→", "🦑"];
+
+// Runs one fuzzed input through the day's parser and both parts, giving the
+// results if it parsed. Arbitrary bytes aren't always valid UTF-8, and the
+// runner reads its input into a String, so they're decoded lossily first.
+pub fn fuzz_one<S: Solution>(data: &[u8]) -> Option<Vec::<PartResult>> {
+    solve::<S>(&String::from_utf8_lossy(data), None).ok()
+}
+
+// Fuzzes a day in this process for the given number of iterations, giving
+// the first input that panics. Used by each day's tests, the runner's fuzz
+// command does the same but can also catch hangs and stack overflows.
+pub fn find_crash<S: Solution>(seed: u64, corpus: &[&str], iterations: usize) -> Option<Vec::<u8>> {
+    let mut rng = Rng::new(seed);
+    let mut corpus: Vec::<Vec::<u8>> = corpus.iter().map(|input| input.as_bytes().to_vec()).collect();
+    let mut seen_results = HashSet::<String>::new();
+
+    for _ in 0..iterations {
+        let input = rng.pick(&corpus).clone();
+        let mutated = mutate(&mut rng, &input, &corpus, MAX_INPUT_LEN);
+
+        match panic::catch_unwind(AssertUnwindSafe(|| fuzz_one::<S>(&mutated))) {
+            Ok(Some(results)) => {
+                if is_new(&mut seen_results, &results) {
+                    corpus.push(mutated);
+                }
+            },
+            Ok(None) => {},
+            Err(_) => return Some(mutated),
+        }
+    }
+
+    None
+}
+
+// Without any way of seeing which code an input reaches, the next best thing
+// is to keep the inputs that get answers we haven't seen yet. Otherwise the
+// corpus fills up with inputs that parse but do nothing interesting.
+pub fn is_new(seen_results: &mut HashSet::<String>, results: &[PartResult]) -> bool {
+    seen_results.insert(format!("{:?}", results))
+}
+
+// Used by each day's tests to fuzz its parser and solution from its test input
+#[track_caller]
+pub fn assert_fuzzed<S: Solution>(corpus: &[&str], iterations: usize) {
+    if let Some(input) = find_crash::<S>(0, corpus, iterations) {
+        panic!("fuzzing panicked on {:?}", String::from_utf8_lossy(&input));
+    }
+}
+
+// The longest input the fuzzer makes by default, long enough for any of the
+// test inputs with room to grow
+pub const MAX_INPUT_LEN: usize = 4096;
+
+// Makes a new input from one in the corpus, applying a few mutations in a row
+pub fn mutate(rng: &mut Rng, input: &[u8], corpus: &[Vec::<u8>], max_len: usize) -> Vec::<u8> {
+    let mut output = input.to_vec();
+
+    for _ in 0..1 + rng.index(4) {
+        mutate_once(rng, &mut output, corpus);
+    }
+
+    output.truncate(max_len);
+    output
+}
+
+fn mutate_once(rng: &mut Rng, data: &mut Vec::<u8>, corpus: &[Vec::<u8>]) {
+    // Everything below needs somewhere to work, an empty input can only grow
+    if data.is_empty() {
+        data.push(*rng.pick(INTERESTING_BYTES));
+        return
+    }
+
+    let at = rng.index(data.len());
+
+    match rng.index(10) {
+        // Flip a bit
+        0 => data[at] ^= 1 << rng.index(8),
+        // Replace a byte with one that means something
+        1 => data[at] = *rng.pick(INTERESTING_BYTES),
+        // Insert a byte that means something
+        2 => data.insert(at, *rng.pick(INTERESTING_BYTES)),
+        // Delete a run of bytes
+        3 => {
+            let len = 1 + rng.index((data.len() - at).min(16));
+            data.drain(at..at + len);
+        },
+        // Duplicate a run of bytes, e.g. making a line or card longer
+        4 => {
+            let len = 1 + rng.index((data.len() - at).min(64));
+            let run = data[at..at + len].to_vec();
+            data.splice(at..at, run);
+        },
+        // Replace a whole number with one on a boundary, or insert one if
+        // we're not in a number
+        5 => {
+            let start = data[..at].iter().rposition(|b| !b.is_ascii_digit()).map_or(0, |i| i + 1);
+            let end = data[at..].iter().position(|b| !b.is_ascii_digit()).map_or(data.len(), |i| at + i);
+            data.splice(start..end, rng.pick(INTERESTING_NUMBERS).bytes());
+        },
+        // Insert a character that's more than one byte long
+        6 => {
+            data.splice(at..at, rng.pick(MULTIBYTE).bytes());
+        },
+        // Chop the end off
+        7 => data.truncate(at),
+        // Swap in part of another input from the corpus
+        8 => {
+            let other = rng.pick(corpus);
+            if !other.is_empty() {
+                let start = rng.index(other.len());
+                let len = 1 + rng.index(other.len() - start);
+                data.splice(at.., other[start..start + len].iter().copied());
+            }
+        },
+        // Any byte at all
+        _ => data[at] = rng.next_u64() as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutations_are_deterministic_and_bounded() {
+        let corpus = vec![b"199\n200\n208".to_vec()];
+
+        let mutated: Vec::<Vec::<u8>> = (0..200)
+            .scan(Rng::new(4), |rng, _| Some(mutate(rng, &corpus[0], &corpus, 32)))
+            .collect();
+        let again: Vec::<Vec::<u8>> = (0..200)
+            .scan(Rng::new(4), |rng, _| Some(mutate(rng, &corpus[0], &corpus, 32)))
+            .collect();
+
+        assert_eq!(mutated, again);
+        assert!(mutated.iter().all(|m| m.len() <= 32));
+        assert!(mutated.iter().any(|m| *m != corpus[0]));
+    }
+
+    struct Fragile;
+
+    impl Solution for Fragile {
+        type Input = Vec::<u32>;
+
+        fn parse(input: &str) -> Result<Vec::<u32>, crate::ParseError> {
+            Ok(input.split(',').filter_map(|n| n.parse().ok()).collect())
+        }
+
+        // Falls over on an empty list
+        fn part1(input: &Vec::<u32>) -> Option<crate::Answer> {
+            Some(input[0].into())
+        }
+
+        fn part2(_input: &Vec::<u32>) -> Option<crate::Answer> {
+            None
+        }
+    }
+
+    #[test]
+    fn crashes_are_found() {
+        let crash = find_crash::<Fragile>(0, &["1,2,3"], 1000);
+        assert!(crash.is_some_and(|c| String::from_utf8_lossy(&c).split(',').all(|n| n.parse::<u32>().is_err())));
+    }
+
+    #[test]
+    fn empty_inputs_grow() {
+        let mut rng = Rng::new(0);
+        assert!(!mutate(&mut rng, &[], &[Vec::new()], 10).is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Answer, Rng, Solution};
+
+// How big a generated input should be. Each day uses the knobs that make
+// sense for its puzzle, e.g. the number of bingo cards or the width of an
+// octopus grid, and falls back to the size of the real input for anything
+// that isn't given.
+#[derive(Clone, Debug, Default)]
+pub struct GenOptions {
+    pub size: Option<usize>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub bits: Option<usize>,
+    pub range: Option<usize>,
+}
+
+// A made up puzzle input, along with its answers when the generator can work
+// them out (either by construction or with a simple reference
+// implementation) without trusting the solution itself
+#[derive(Clone, Debug)]
+pub struct Generated {
+    pub input: String,
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+// Implemented by every day to make inputs for stress testing and benchmarking
+pub trait Generator {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated;
+}
+
+// The same seed and options always give the same input
+pub fn generate<G: Generator>(seed: u64, options: &GenOptions) -> Generated {
+    G::generate(&mut Rng::new(seed), options)
+}
+
+// Used by each day's tests to check that a generated input parses, and that
+// the solution agrees with the generator's answers
+#[track_caller]
+pub fn assert_generated<S: Solution + Generator>(seed: u64, options: &GenOptions) {
+    let generated = generate::<S>(seed, options);
+    let parsed = S::parse(&generated.input)
+        .unwrap_or_else(|e| panic!("Failed to parse generated input: {}", e));
+
+    if let Some(part1) = generated.part1 {
+        assert_eq!(S::part1(&parsed), Some(part1), "wrong answer to part 1 for seed {}", seed);
+    }
+    if let Some(part2) = generated.part2 {
+        assert_eq!(S::part2(&parsed), Some(part2), "wrong answer to part 2 for seed {}", seed);
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::{Index, IndexMut};
+
+use crate::ParseError;
+
+// A rectangular grid of cells, indexed by (x, y) with (0, 0) in the top left.
+// The cells are stored a row at a time, so grid[(x, y)] is the same point as
+// rows[y][x] in the input file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec::<T>,
+}
+
+// Offsets to the neighbours of a point, going clockwise from the one above
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1),
+];
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+
+    // Every row needs to be the same width, otherwise we get None back
+    pub fn from_rows(rows: Vec::<Vec::<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+
+        if rows.iter().any(|row| row.len() != width) {
+            return None
+        }
+
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    // Parses a map with one character per cell, e.g. a heightmap of digits.
+    // name is what a single cell is called in the puzzle ("height"), and is
+    // used for the error messages along with cell_expected.
+    pub fn parse(
+        input: &str, name: &str, cell_expected: &str, parse_cell: impl Fn(char) -> Option<T>
+    ) -> Result<Grid<T>, ParseError> {
+        let mut rows = Vec::<Vec::<T>>::new();
+
+        for (i, line) in crate::input::lines(input) {
+            let row: Vec::<T> = line
+                .char_indices()
+                .map(|(pos, c)| {
+                    parse_cell(c).ok_or_else(|| {
+                        ParseError::new(i, line, &line[pos..pos + c.len_utf8()], cell_expected)
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            // Every row needs to be the same width for the map to be a rectangle
+            if row.is_empty() || rows.first().is_some_and(|first| first.len() != row.len()) {
+                let width = rows.first().map_or(1, |first| first.len());
+                let expected = format!("a row of {} {}s", width, name);
+                return Err(ParseError::new(i, line, line, &expected))
+            }
+
+            rows.push(row);
+        }
+
+        if rows.is_empty() {
+            let expected = format!("a map of {}s", name);
+            return Err(ParseError::new(0, input, input, &expected))
+        }
+
+        // we've checked every row is the same width above
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    // The points up, down, left and right of (x, y) which are on the grid
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_points(x, y, &OFFSETS_4)
+    }
+
+    // The same as neighbors4, but including the diagonals too
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_points(x, y, &OFFSETS_8)
+    }
+
+    fn offset_points(
+        &self, x: usize, y: usize, offsets: &'static [(isize, isize)]
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(*dx)?;
+            let y = y.checked_add_signed(*dy)?;
+
+            (x < self.width && y < self.height).then_some((x, y))
+        })
+    }
+
+    // Every (x, y) on the grid, a row at a time
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    // Every cell on the grid, a row at a time
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a chunk size of 0, which we'd have for an empty grid
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+}
+
+impl Grid<u32> {
+    // Parses a map of single digits, like the heightmap on day 9
+    pub fn parse_digits(input: &str, name: &str) -> Result<Grid<u32>, ParseError> {
+        let cell_expected = format!("a{} {} from 0 to 9", article_suffix(name), name);
+        Grid::parse(input, name, &cell_expected, |c| c.to_digit(10))
+    }
+}
+
+impl Grid<char> {
+    // Parses a map where every character is its own cell
+    pub fn parse_chars(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(input, "character", "a character", Some)
+    }
+}
+
+// "an energy level" but "a height"
+fn article_suffix(name: &str) -> &'static str {
+    if name.starts_with(['a', 'e', 'i', 'o', 'u']) { "n" } else { "" }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of a {}x{} grid", x, y, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of a {}x{} grid", x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<u32> {
+        Grid::parse_digits("123\n456", "digit").unwrap()
+    }
+
+    #[test]
+    fn cells_are_indexed_by_x_then_y() {
+        let grid = example();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid.get(0, 1), Some(&4));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let grid = example();
+
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors8(0, 0).collect::<Vec<_>>(), vec![(1, 0), (1, 1), (0, 1)]);
+        assert_eq!(grid.neighbors8(1, 1).count(), 5);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = example();
+
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.points().last(), Some((2, 1)));
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+
+        let error = Grid::parse_digits("123\n45", "height").unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (2, "a row of 3 heights"));
+
+        let error = Grid::parse_digits("1x3", "energy level").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (2, "an energy level from 0 to 9"));
+    }
+
+    #[test]
+    fn chars_are_kept_as_they_are() {
+        let grid = Grid::parse_chars("#.\n.#").unwrap();
+        assert_eq!(grid.iter().filter(|c| **c == '#').count(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::Write;
+
+use crate::Grid;
+
+pub type Colour = [u8; 3];
+
+// A picture of a day's state, one pixel per grid cell, which can be written
+// out as a PGM, PPM or SVG without needing an image library
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid::<Colour>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Pgm,
+    Ppm,
+    Svg,
+}
+
+impl ImageFormat {
+    // Works out the format from the extension of the file we're writing to
+    pub fn from_path(path: &str) -> Option<ImageFormat> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "pgm" => Some(ImageFormat::Pgm),
+            "ppm" => Some(ImageFormat::Ppm),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+impl Image {
+    pub fn new(pixels: Grid::<Colour>) -> Image {
+        Image { pixels }
+    }
+
+    // Colours each cell of a grid to make a pixel
+    pub fn from_grid<T>(grid: &Grid::<T>, colour: impl Fn(&T) -> Colour) -> Image {
+        let mut pixels = Grid::new(grid.width(), grid.height(), [0; 3]);
+
+        for (pixel, cell) in pixels.iter_mut().zip(grid.iter()) {
+            *pixel = colour(cell);
+        }
+
+        Image { pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Colour> {
+        self.pixels.get(x, y).copied()
+    }
+
+    // Blows every pixel up into a scale x scale square, so small grids like
+    // the 10x10 octopuses are big enough to see
+    pub fn scaled(&self, scale: usize) -> Image {
+        let mut pixels = Grid::new(self.width() * scale, self.height() * scale, [0; 3]);
+
+        for (x, y) in pixels.points() {
+            pixels[(x, y)] = self.pixels[(x / scale, y / scale)];
+        }
+
+        Image { pixels }
+    }
+
+    pub fn encode(&self, format: ImageFormat) -> Vec::<u8> {
+        match format {
+            ImageFormat::Pgm => self.to_pgm(),
+            ImageFormat::Ppm => self.to_ppm(),
+            ImageFormat::Svg => self.to_svg().into_bytes(),
+        }
+    }
+
+    // Binary greyscale, using the usual luma weights to flatten the colours
+    fn to_pgm(&self) -> Vec::<u8> {
+        let mut bytes = format!("P5\n{} {}\n255\n", self.width(), self.height()).into_bytes();
+
+        bytes.extend(self.pixels.iter().map(|[r, g, b]| {
+            ((299 * *r as u32 + 587 * *g as u32 + 114 * *b as u32) / 1000) as u8
+        }));
+
+        bytes
+    }
+
+    // Binary RGB
+    fn to_ppm(&self) -> Vec::<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width(), self.height()).into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+
+        bytes
+    }
+
+    // One rect per run of the same colour in a row, which keeps the file a
+    // sensible size for the mostly empty day 5 map
+    fn to_svg(&self) -> String {
+        let (width, height) = (self.width(), self.height());
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n\
+             <rect width=\"{}\" height=\"{}\" fill=\"#000000\"/>\n",
+            width, height, width, height, width, height
+        );
+
+        for (y, row) in self.pixels.rows().enumerate() {
+            let mut x = 0;
+
+            for run in row.chunk_by(|a, b| a == b) {
+                let [r, g, b] = run[0];
+
+                // black is the background so there's no need to draw it
+                if run[0] != [0; 3] {
+                    writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                        x, y, run.len(), r, g, b
+                    ).unwrap();
+                }
+
+                x += run.len();
+            }
+        }
+
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+// A colour from dark blue through to yellow for value out of max, for
+// showing counts and levels
+pub fn heat(value: u32, max: u32) -> Colour {
+    if max == 0 {
+        return [0; 3]
+    }
+
+    let t = value.min(max) as f64 / max as f64;
+    let channel = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+
+    [channel(30.0, 255.0), channel(30.0, 220.0), channel(120.0, 40.0)]
+}
+
+// Scales a colour's brightness, where 1.0 leaves it as it is
+pub fn shade(colour: Colour, brightness: f64) -> Colour {
+    colour.map(|c| (c as f64 * brightness).round().clamp(0.0, 255.0) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Image {
+        let grid = Grid::from_rows(vec![vec![0, 1, 1], vec![2, 0, 0]]).unwrap();
+        Image::from_grid(&grid, |c| [*c * 100, 0, 0])
+    }
+
+    #[test]
+    fn format_comes_from_the_extension() {
+        assert_eq!(ImageFormat::from_path("out/vents.PPM"), Some(ImageFormat::Ppm));
+        assert_eq!(ImageFormat::from_path("basins.svg"), Some(ImageFormat::Svg));
+        assert_eq!(ImageFormat::from_path("basins.png"), None);
+        assert_eq!(ImageFormat::from_path("basins"), None);
+    }
+
+    #[test]
+    fn ppm_has_a_header_then_rgb_bytes() {
+        let ppm = example().encode(ImageFormat::Ppm);
+
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(&ppm[11..], &[0, 0, 0, 100, 0, 0, 100, 0, 0, 200, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn pgm_has_one_byte_per_pixel() {
+        let pgm = example().encode(ImageFormat::Pgm);
+
+        assert!(pgm.starts_with(b"P5\n3 2\n255\n"));
+        assert_eq!(&pgm[11..], &[0, 29, 29, 59, 0, 0]);
+    }
+
+    #[test]
+    fn svg_merges_runs_and_skips_the_background() {
+        let svg = String::from_utf8(example().encode(ImageFormat::Svg)).unwrap();
+
+        assert!(svg.contains("<rect x=\"1\" y=\"0\" width=\"2\" height=\"1\" fill=\"#640000\"/>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"1\" width=\"1\" height=\"1\" fill=\"#c80000\"/>"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+    }
+
+    #[test]
+    fn scaling_repeats_pixels() {
+        let image = example().scaled(2);
+
+        assert_eq!((image.width(), image.height()), (6, 4));
+        assert_eq!(image.pixel(3, 1), Some([100, 0, 0]));
+        assert_eq!(image.pixel(1, 3), Some([200, 0, 0]));
+    }
+}
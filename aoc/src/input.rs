@@ -0,0 +1,249 @@
+use std::io::{self, BufRead};
+use std::ops::Range;
+
+use crate::{Grid, ParseError};
+
+// Reading the days' inputs the same way everywhere. Inputs come from files
+// saved on all sorts of machines and pasted into all sorts of editors, so
+// every reader here ignores a \r at the end of a line, any other whitespace at
+// the end of a line, and blank lines at the end of the input. The line indices
+// they give are those of the original input, and the lines are slices of it,
+// so a ParseError still points at the right line and column.
+//
+// There's a streaming version of each reader for input that shouldn't be read
+// into memory all at once, like a long stream of depths from stdin.
+
+// Every line of the input, with its index
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    // An empty input has no lines at all, rather than one empty one
+    let input = input.trim_end();
+    let lines = if input.is_empty() { None } else { Some(input.split('\n')) };
+
+    lines.into_iter().flatten().map(str::trim_end).enumerate()
+}
+
+// The items of a comma separated list on one line, e.g. the crabs in day 7.
+// Whitespace around each item is ignored.
+pub fn comma_list(line: &str) -> impl Iterator<Item = &str> {
+    let line = line.trim();
+    let items = if line.is_empty() { None } else { Some(line.split(',')) };
+
+    items.into_iter().flatten().map(str::trim)
+}
+
+// For the days whose input is a single line. Anything after the first line
+// is an error, rather than being quietly ignored.
+pub fn single_line(input: &str) -> Result<&str, ParseError> {
+    let mut lines = lines(input);
+    let first = lines.next().map_or("", |(_, line)| line);
+
+    match lines.next() {
+        Some((i, line)) => Err(ParseError::new(i, line, line, "the end of the input after one line")),
+        None => Ok(first),
+    }
+}
+
+// Groups of lines separated by one or more blank lines, e.g. the bingo cards
+// in day 4
+pub fn paragraphs(input: &str) -> Vec::<Vec::<(usize, &str)>> {
+    let mut paragraphs = vec![Vec::new()];
+
+    for (i, line) in lines(input) {
+        if !line.is_empty() {
+            paragraphs.last_mut().unwrap().push((i, line));
+        } else if !paragraphs.last().unwrap().is_empty() {
+            paragraphs.push(Vec::new());
+        }
+    }
+
+    paragraphs.retain(|paragraph| !paragraph.is_empty());
+    paragraphs
+}
+
+// A rectangle of digits, e.g. the heights in day 9. name is what each digit
+// is, for the errors.
+pub fn digit_grid(input: &str, name: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse_digits(input, name)
+}
+
+// The streaming version of `lines`. Blank lines are held back until we know
+// they aren't the blank lines at the end.
+pub struct StreamLines<R> {
+    lines: io::Lines<R>,
+    next_index: usize,
+    blanks: Range<usize>,
+    held: Option<(usize, String)>,
+}
+
+pub fn stream_lines<R: BufRead>(reader: R) -> StreamLines<R> {
+    StreamLines { lines: reader.lines(), next_index: 0, blanks: 0..0, held: None }
+}
+
+impl<R: BufRead> Iterator for StreamLines<R> {
+    type Item = io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<io::Result<(usize, String)>> {
+        loop {
+            if self.held.is_some() {
+                if let Some(i) = self.blanks.next() {
+                    return Some(Ok((i, String::new())))
+                }
+
+                return self.held.take().map(Ok)
+            }
+
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let index = self.next_index;
+            self.next_index += 1;
+
+            let line = line.trim_end();
+            if line.is_empty() {
+                if self.blanks.is_empty() {
+                    self.blanks = index..index;
+                }
+                self.blanks.end = index + 1;
+            } else {
+                self.held = Some((index, line.to_string()));
+            }
+        }
+    }
+}
+
+// The streaming version of `paragraphs`
+pub struct StreamParagraphs<R> {
+    lines: StreamLines<R>,
+}
+
+pub fn stream_paragraphs<R: BufRead>(reader: R) -> StreamParagraphs<R> {
+    StreamParagraphs { lines: stream_lines(reader) }
+}
+
+impl<R: BufRead> Iterator for StreamParagraphs<R> {
+    type Item = io::Result<Vec::<(usize, String)>>;
+
+    fn next(&mut self) -> Option<io::Result<Vec::<(usize, String)>>> {
+        let mut paragraph = Vec::new();
+
+        for line in self.lines.by_ref() {
+            match line {
+                Ok((_, line)) if line.is_empty() && paragraph.is_empty() => continue,
+                Ok((_, line)) if line.is_empty() => break,
+                Ok(line) => paragraph.push(line),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        (!paragraph.is_empty()).then_some(Ok(paragraph))
+    }
+}
+
+// The streaming version of `comma_list`, which reads one item at a time so a
+// huge list never has to be in memory at once
+pub struct StreamCommaList<R> {
+    reader: R,
+}
+
+pub fn stream_comma_list<R: BufRead>(reader: R) -> StreamCommaList<R> {
+    StreamCommaList { reader }
+}
+
+impl<R: BufRead> Iterator for StreamCommaList<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut item = Vec::new();
+
+        match self.reader.read_until(b',', &mut item) {
+            Ok(0) => None,
+            Ok(_) => {
+                let item = String::from_utf8_lossy(&item);
+                let item = item.strip_suffix(',').unwrap_or(&item).trim();
+
+                // Only the trailing whitespace can make an empty last item
+                if item.is_empty() && !self.reader.fill_buf().is_ok_and(|rest| !rest.is_empty()) {
+                    return None
+                }
+
+                Some(Ok(item.to_string()))
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "199 \r\n200\r\n\r\n208\t\r\n\r\n\n";
+
+    #[test]
+    fn lines_ignore_carriage_returns_and_trailing_whitespace() {
+        let read: Vec::<(usize, &str)> = lines(MESSY).collect();
+        assert_eq!(read, vec![(0, "199"), (1, "200"), (2, ""), (3, "208")]);
+
+        assert_eq!(lines(" \n\n").count(), 0);
+        assert_eq!(lines("").count(), 0);
+    }
+
+    #[test]
+    fn errors_still_point_at_the_original_input() {
+        let input = "1,2\r\n3,x\n";
+        let (i, line) = lines(input).nth(1).unwrap();
+        let error = crate::parse_token::<u32>(i, line, comma_list(line).nth(1).unwrap(), "a number").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "x"));
+    }
+
+    #[test]
+    fn comma_lists_ignore_whitespace() {
+        assert_eq!(comma_list("3,4, 3 ,1\r").collect::<Vec<_>>(), vec!["3", "4", "3", "1"]);
+        assert_eq!(comma_list(" ").count(), 0);
+        assert_eq!(comma_list("1,,2").collect::<Vec<_>>(), vec!["1", "", "2"]);
+    }
+
+    #[test]
+    fn single_line_inputs_can_end_in_a_newline() {
+        assert_eq!(single_line("16,1,2\r\n"), Ok("16,1,2"));
+        assert_eq!(single_line("16,1\n2").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn paragraphs_are_split_on_blank_lines() {
+        let read = paragraphs("\n7,4\r\n\r\n\r\n1 2\n3 4\n\n5 6\n\n");
+        assert_eq!(read, vec![vec![(1, "7,4")], vec![(4, "1 2"), (5, "3 4")], vec![(7, "5 6")]]);
+    }
+
+    #[test]
+    fn digit_grids_can_end_in_a_newline() {
+        let grid = digit_grid("123\r\n456\r\n", "height").unwrap();
+        assert_eq!((grid.width(), grid.height(), grid[(2, 1)]), (3, 2, 6));
+    }
+
+    #[test]
+    fn streaming_matches_reading_it_all() {
+        let streamed: Vec::<(usize, String)> = stream_lines(MESSY.as_bytes()).map(Result::unwrap).collect();
+        let read: Vec::<(usize, String)> = lines(MESSY).map(|(i, line)| (i, line.to_string())).collect();
+        assert_eq!(streamed, read);
+
+        let input = "\n7,4\r\n\r\n\r\n1 2\n3 4\n\n5 6\n\n";
+        let streamed: Vec::<Vec::<(usize, String)>> = stream_paragraphs(input.as_bytes()).map(Result::unwrap).collect();
+        let read: Vec::<Vec::<(usize, String)>> = paragraphs(input)
+            .into_iter()
+            .map(|paragraph| paragraph.into_iter().map(|(i, line)| (i, line.to_string())).collect())
+            .collect();
+        assert_eq!(streamed, read);
+    }
+
+    #[test]
+    fn streamed_comma_lists_match_reading_it_all() {
+        for input in ["3,4, 3 ,1\r\n", "1,,2", "", "5"] {
+            let streamed: Vec::<String> = stream_comma_list(input.as_bytes()).map(Result::unwrap).collect();
+            assert_eq!(streamed, comma_list(input).collect::<Vec<_>>(), "{:?}", input);
+        }
+    }
+}
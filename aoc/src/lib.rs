@@ -0,0 +1,49 @@
+// Helpers shared between all of the days, so that each day doesn't need its
+// own copy of the file reading code.
+use std::fs;
+use std::io::{self, Error, Read};
+
+mod answer;
+mod answers;
+mod bench;
+mod fuzz;
+mod generate;
+mod grid;
+mod image;
+pub mod input;
+mod parse_error;
+pub mod parser;
+pub mod property;
+mod rng;
+mod solution;
+mod terminal;
+pub mod trace;
+mod variant;
+
+pub use answer::{Answer, Detail, Details};
+pub use answers::{parse_answers, RegisteredAnswer};
+pub use bench::{bench, summarise, Summary, Timings};
+pub use fuzz::{assert_fuzzed, find_crash, fuzz_one, is_new, mutate, MAX_INPUT_LEN};
+pub use generate::{assert_generated, generate, GenOptions, Generated, Generator};
+pub use grid::Grid;
+pub use image::{heat, shade, Colour, Image, ImageFormat};
+pub use parse_error::{parse_token, ParseError};
+pub use rng::Rng;
+pub use solution::{
+    animate, assert_answers, print_answers, render, run_file, solve, solve_timed, Frames, Part, PartResult,
+    Solution, Timed,
+};
+pub use terminal::{highlight, paint, strip_ansi};
+pub use variant::{cross_check, CrossCheck, Variant, MAX_WORK};
+
+// Reads the whole of an input file into a single string. A filepath of "-"
+// reads from stdin instead.
+pub fn get_file_input(filepath: &str) -> Result<String, Error> {
+    if filepath == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(input)
+    }
+
+    fs::read_to_string(filepath)
+}
@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// An error from parsing a day's input, pointing at exactly where in the input
+// things went wrong and what we were expecting to find there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    // Both of these start from 1, like in an editor
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    // line_index is the index from `lines().enumerate()`, i.e. starting at 0,
+    // and text should be a slice of line so that we can work out the column
+    pub fn new(line_index: usize, line: &str, text: &str, expected: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line: line_index + 1,
+            column: column_of(line, text),
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    // The days only see the contents of the file, so the caller that read
+    // the file adds its name afterwards
+    pub fn with_file(mut self, file: &str) -> ParseError {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+// Finds where text starts within line, or the start of the line if text isn't
+// a slice of it
+fn column_of(line: &str, text: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let text_start = text.as_ptr() as usize;
+
+    if text_start >= line_start && text_start + text.len() <= line_start + line.len() {
+        line[..text_start - line_start].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+
+        write!(
+            f,
+            "{}:{}: found {:?}, expected {}",
+            self.line, self.column, self.text, self.expected
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// Parses a single token from a line, e.g. a number, turning a failure into a
+// ParseError pointing at the token
+pub fn parse_token<T: FromStr>(
+    line_index: usize, line: &str, token: &str, expected: &str
+) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(line_index, line, token, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_is_found_from_the_token() {
+        let line = "forward 5x";
+        let error = ParseError::new(2, line, &line[8..], "a distance");
+
+        assert_eq!((error.line, error.column), (3, 9));
+        assert_eq!(error.to_string(), "3:9: found \"5x\", expected a distance");
+    }
+
+    #[test]
+    fn file_is_included_when_known() {
+        let error = ParseError::new(0, "", "", "a depth").with_file("input.txt");
+        assert_eq!(error.to_string(), "input.txt:1:1: found \"\", expected a depth");
+    }
+
+    #[test]
+    fn parse_token_points_at_the_bad_token() {
+        let line = "1,2,x,4";
+        let error = parse_token::<u32>(0, line, &line[4..5], "a number").unwrap_err();
+
+        assert_eq!(error.column, 5);
+        assert_eq!(parse_token::<u32>(0, line, &line[2..3], "a number"), Ok(2));
+    }
+}
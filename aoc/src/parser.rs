@@ -0,0 +1,324 @@
+use std::str::FromStr;
+
+use crate::{Grid, ParseError};
+
+// A few small parser combinators for the shapes the inputs come in: numbers,
+// lists with separators, literal text, blocks of lines separated by blank
+// lines, and grids. Each parser takes a Cursor into a line and gives back what
+// it parsed along with the rest of the line, or a ParseError pointing at the
+// exact text it couldn't make sense of.
+//
+// The blocks themselves come from `input::paragraphs`, and are parsed a line
+// at a time with `rows` and `one_line`.
+
+// Where a parser has got to: the whole line, so that errors can work out their
+// column, and what's left of it
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    pub line_index: usize,
+    pub line: &'a str,
+    pub rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line_index: usize, line: &'a str) -> Cursor<'a> {
+        Cursor { line_index, line, rest: line }
+    }
+
+    // text should be a slice of the line, as with ParseError::new
+    pub fn error(&self, text: &str, expected: &str) -> ParseError {
+        ParseError::new(self.line_index, self.line, text, expected)
+    }
+
+    fn advance(self, len: usize) -> Cursor<'a> {
+        Cursor { rest: &self.rest[len..], ..self }
+    }
+
+    // The text between here and a cursor further along the line
+    fn up_to(&self, later: &Cursor<'a>) -> &'a str {
+        &self.rest[..self.rest.len() - later.rest.len()]
+    }
+}
+
+pub type Parsed<'a, T> = Result<(T, Cursor<'a>), ParseError>;
+
+// Anything that can parse a T from a cursor. Every closure with the right
+// signature is one, so parsers are built up by passing closures around.
+pub trait Parser<'a, T>: Fn(Cursor<'a>) -> Parsed<'a, T> {}
+
+impl<'a, T, F: Fn(Cursor<'a>) -> Parsed<'a, T>> Parser<'a, T> for F {}
+
+// A token runs up to the next whitespace or punctuation, apart from the signs
+// so that "-1" is one token. It's what an error points at when a parser can't
+// use what it finds, so "2O0" is reported as a whole rather than from the O.
+fn token_len(text: &str) -> usize {
+    text.find(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '-' && c != '+'))
+        .unwrap_or(text.len())
+}
+
+// What to point at when there isn't a token: the next character, or nothing
+// at the end of the line
+fn next_char(text: &str) -> &str {
+    &text[..text.chars().next().map_or(0, char::len_utf8)]
+}
+
+// The next token, which can't be empty
+pub fn token<'a>(expected: &str) -> impl Parser<'a, &'a str> {
+    let expected = expected.to_string();
+
+    move |cursor: Cursor<'a>| {
+        match token_len(cursor.rest) {
+            0 => Err(cursor.error(next_char(cursor.rest), &expected)),
+            len => Ok((&cursor.rest[..len], cursor.advance(len))),
+        }
+    }
+}
+
+// A number, or anything else with a FromStr, made from the next token
+pub fn integer<'a, T: FromStr>(expected: &str) -> impl Parser<'a, T> {
+    let expected = expected.to_string();
+
+    try_map(token(&expected), &expected, |token| token.parse().ok())
+}
+
+// Exactly the given text
+pub fn literal<'a>(text: &str) -> impl Parser<'a, ()> {
+    let text = text.to_string();
+    let expected = format!("{:?}", text);
+
+    move |cursor: Cursor<'a>| {
+        if cursor.rest.starts_with(&text) {
+            return Ok(((), cursor.advance(text.len())))
+        }
+
+        // Point at as much as the literal would have covered
+        let found_len: usize = cursor.rest.chars().take(text.chars().count()).map(char::len_utf8).sum();
+        Err(cursor.error(&cursor.rest[..found_len], &expected))
+    }
+}
+
+// At least one space or tab
+pub fn whitespace<'a>() -> impl Parser<'a, ()> {
+    move |cursor: Cursor<'a>| {
+        match cursor.rest.find(|c| c != ' ' && c != '\t').unwrap_or(cursor.rest.len()) {
+            0 => Err(cursor.error(next_char(cursor.rest), "a space")),
+            len => Ok(((), cursor.advance(len))),
+        }
+    }
+}
+
+// Any number of spaces or tabs, including none
+pub fn optional_whitespace<'a>() -> impl Parser<'a, ()> {
+    move |cursor: Cursor<'a>| {
+        let len = cursor.rest.find(|c| c != ' ' && c != '\t').unwrap_or(cursor.rest.len());
+        Ok(((), cursor.advance(len)))
+    }
+}
+
+// p with any whitespace around it, e.g. the commas in "3, 4 ,5"
+pub fn padded<'a, T>(p: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    preceded(optional_whitespace(), terminated(p, optional_whitespace()))
+}
+
+pub fn map<'a, A, B>(p: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |cursor: Cursor<'a>| {
+        let (value, rest) = p(cursor)?;
+        Ok((f(value), rest))
+    }
+}
+
+// Turns what p parsed into something else, where f can refuse. A refusal is
+// an error pointing at everything p parsed.
+pub fn try_map<'a, A, B>(
+    p: impl Parser<'a, A>, expected: &str, f: impl Fn(A) -> Option<B>
+) -> impl Parser<'a, B> {
+    let expected = expected.to_string();
+
+    move |cursor: Cursor<'a>| {
+        let (value, rest) = p(cursor)?;
+        f(value).map(|value| (value, rest)).ok_or_else(|| cursor.error(cursor.up_to(&rest), &expected))
+    }
+}
+
+// Checks what p parsed, e.g. that a number is in range
+pub fn verify<'a, T>(
+    p: impl Parser<'a, T>, expected: &str, check: impl Fn(&T) -> bool
+) -> impl Parser<'a, T> {
+    try_map(p, expected, move |value| check(&value).then_some(value))
+}
+
+pub fn pair<'a, A, B>(first: impl Parser<'a, A>, second: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |cursor: Cursor<'a>| {
+        let (a, cursor) = first(cursor)?;
+        let (b, cursor) = second(cursor)?;
+        Ok(((a, b), cursor))
+    }
+}
+
+// e.g. the two ends of a line in day 5, "0,9 -> 5,9"
+pub fn separated_pair<'a, A, S, B>(
+    first: impl Parser<'a, A>, separator: impl Parser<'a, S>, second: impl Parser<'a, B>
+) -> impl Parser<'a, (A, B)> {
+    map(pair(first, pair(separator, second)), |(a, (_, b))| (a, b))
+}
+
+// p after something we don't need to keep
+pub fn preceded<'a, S, T>(skipped: impl Parser<'a, S>, p: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    map(pair(skipped, p), |(_, value)| value)
+}
+
+// p followed by something we don't need to keep
+pub fn terminated<'a, T, S>(p: impl Parser<'a, T>, skipped: impl Parser<'a, S>) -> impl Parser<'a, T> {
+    map(pair(p, skipped), |(value, _)| value)
+}
+
+// One or more items with a separator between each of them. A separator that
+// isn't followed by anything that could be an item, like the " " in front of
+// " | " in day 8, is left for whatever comes next. An item that starts but
+// doesn't parse is an error though, so "1,2,x" points at the x.
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>, separator: impl Parser<'a, S>
+) -> impl Parser<'a, Vec::<T>> {
+    move |cursor: Cursor<'a>| {
+        let (first, mut cursor) = item(cursor)?;
+        let mut items = vec![first];
+
+        while let Ok((_, after)) = separator(cursor) {
+            if token_len(after.rest) == 0 {
+                break
+            }
+
+            let (next, rest) = item(after)?;
+            items.push(next);
+            cursor = rest;
+        }
+
+        Ok((items, cursor))
+    }
+}
+
+// Parses the whole of a line, anything left over is an error
+pub fn parse_line<'a, T>(line_index: usize, line: &'a str, p: impl Parser<'a, T>) -> Result<T, ParseError> {
+    let (value, rest) = p(Cursor::new(line_index, line))?;
+
+    if !rest.rest.is_empty() {
+        return Err(rest.error(rest.rest, "the end of the line"))
+    }
+
+    Ok(value)
+}
+
+// Parses every line of the input the same way
+pub fn parse_lines<'a, T>(input: &'a str, p: impl Parser<'a, T>) -> Result<Vec::<T>, ParseError> {
+    crate::input::lines(input)
+        .map(|(i, line)| parse_line(i, line, &p))
+        .collect()
+}
+
+// Parses every line of a block from `input::paragraphs` the same way
+pub fn rows<'a, T>(block: &[(usize, &'a str)], p: impl Parser<'a, T>) -> Result<Vec::<T>, ParseError> {
+    block
+        .iter()
+        .map(|(i, line)| parse_line(*i, line, &p))
+        .collect()
+}
+
+// A block that should only be one line long, like the bingo numbers in day 4.
+// after is what should have come after that line, for the error.
+pub fn one_line<'a, T>(
+    block: &[(usize, &'a str)], p: impl Parser<'a, T>, after: &str
+) -> Result<T, ParseError> {
+    if let Some((i, line)) = block.get(1) {
+        return Err(ParseError::new(*i, line, line, after))
+    }
+
+    // paragraphs never gives an empty block
+    let (i, line) = block[0];
+    parse_line(i, line, p)
+}
+
+// A rectangular grid with a cell for each character, with every row checked
+// to be the same width. name is what each cell is, for the errors.
+pub fn grid<T>(
+    input: &str, name: &str, cell_expected: &str, cell: impl Fn(char) -> Option<T>
+) -> Result<Grid<T>, ParseError> {
+    Grid::parse(input, name, cell_expected, cell)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(error: ParseError) -> (usize, usize, String) {
+        (error.line, error.column, error.text)
+    }
+
+    #[test]
+    fn integers_point_at_the_whole_token() {
+        assert_eq!(parse_line(0, "-12", integer::<i32>("a number")), Ok(-12));
+
+        let error = parse_line(3, "2O0", integer::<u32>("a number")).unwrap_err();
+        assert_eq!(error_at(error), (4, 1, String::from("2O0")));
+
+        let error = parse_line(0, "4294967296", integer::<u32>("a number")).unwrap_err();
+        assert_eq!(error.expected, "a number");
+    }
+
+    #[test]
+    fn lists_point_at_the_bad_item() {
+        let list = || separated(integer::<u32>("a number"), padded(literal(",")));
+        assert_eq!(parse_line(0, "3,4 , 5", list()), Ok(vec![3, 4, 5]));
+
+        let error = parse_line(0, "16,1,two", list()).unwrap_err();
+        assert_eq!(error_at(error), (1, 6, String::from("two")));
+
+        let error = parse_line(0, "1,2,", list()).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (4, "the end of the line"));
+    }
+
+    #[test]
+    fn separators_before_something_else_are_left_alone() {
+        let words = || separated(token("a word"), literal(" "));
+        let line = separated_pair(words(), literal(" | "), words());
+
+        assert_eq!(parse_line(0, "ab cd | ef", line), Ok((vec!["ab", "cd"], vec!["ef"])));
+    }
+
+    #[test]
+    fn literals_point_at_what_was_there_instead() {
+        let arrow = separated_pair(integer::<u32>("a number"), literal(" -> "), integer::<u32>("a number"));
+        let error = parse_line(0, "0 => 9", arrow).unwrap_err();
+
+        assert_eq!((error.column, error.text.as_str(), error.expected.as_str()), (2, " => ", "\" -> \""));
+    }
+
+    #[test]
+    fn failed_checks_point_at_everything_parsed() {
+        let row = verify(
+            preceded(optional_whitespace(), separated(integer::<u32>("a number"), whitespace())),
+            "a row of 3 numbers",
+            |row| row.len() == 3,
+        );
+
+        assert_eq!(parse_line(0, " 1  2 3", &row), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_line(0, " 1  2", &row).unwrap_err().text, " 1  2");
+    }
+
+    #[test]
+    fn blocks_are_parsed_a_line_at_a_time() {
+        let blocks = crate::input::paragraphs("7,4\n\n1 2\n3 x\n");
+
+        let list = separated(integer::<u32>("a number"), literal(","));
+        assert_eq!(one_line(&blocks[0], list, "a blank line"), Ok(vec![7, 4]));
+        assert_eq!(one_line(&blocks[1], integer::<u32>("a number"), "a blank line").unwrap_err().line, 4);
+
+        let numbers = || separated(integer::<u32>("a number"), whitespace());
+        assert_eq!(error_at(rows(&blocks[1], numbers()).unwrap_err()), (4, 3, String::from("x")));
+    }
+
+    #[test]
+    fn errors_handle_multibyte_characters() {
+        let error = parse_line(0, "1,é", separated(integer::<u32>("a number"), literal(","))).unwrap_err();
+        assert_eq!(error_at(error), (1, 3, String::from("é")));
+    }
+}
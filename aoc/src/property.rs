@@ -0,0 +1,210 @@
+use std::env;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use crate::Rng;
+
+// A tiny property testing library, used to check the days' shortcuts against
+// brute force versions on lots of random inputs. When a property fails, the
+// input is shrunk down to the smallest one we can find which still fails, so
+// the counterexample is something we can actually reason about.
+
+type GenerateFn<T> = Box<dyn Fn(&mut Rng) -> T>;
+type ShrinkFn<T> = Box<dyn Fn(&T) -> Vec::<T>>;
+
+// How to make random values of T, and how to make a failing one smaller
+pub struct Strategy<T> {
+    generate: GenerateFn<T>,
+    shrink: ShrinkFn<T>,
+}
+
+impl<T> Strategy<T> {
+    pub fn new(
+        generate: impl Fn(&mut Rng) -> T + 'static, shrink: impl Fn(&T) -> Vec::<T> + 'static
+    ) -> Strategy<T> {
+        Strategy { generate: Box::new(generate), shrink: Box::new(shrink) }
+    }
+
+    pub fn generate(&self, rng: &mut Rng) -> T {
+        (self.generate)(rng)
+    }
+
+    // Smaller versions of value, roughly the most aggressive first
+    pub fn shrink(&self, value: &T) -> Vec::<T> {
+        (self.shrink)(value)
+    }
+}
+
+// Any number in the range, shrinking towards the start of it
+pub fn numbers(range: RangeInclusive<u64>) -> Strategy<u64> {
+    let (start, end) = (*range.start(), *range.end());
+
+    Strategy::new(
+        // The whole of u64 is one more number than fits in a u64
+        move |rng| match (end - start).checked_add(1) {
+            Some(span) => start + rng.next_u64() % span,
+            None => rng.next_u64(),
+        },
+        move |n: &u64| {
+            let mut smaller = vec![start, start + (n - start) / 2, n.saturating_sub(1)];
+            smaller.retain(|s| s < n && *s >= start);
+            smaller.dedup();
+            smaller
+        },
+    )
+}
+
+// A Vec with a length in the range, shrinking by taking elements out and then
+// by shrinking the elements themselves
+pub fn vecs<T: Clone + 'static>(
+    element: Strategy<T>, lengths: RangeInclusive<usize>
+) -> Strategy<Vec::<T>> {
+    let (min_len, max_len) = (*lengths.start(), *lengths.end());
+    let element = Rc::new(element);
+    let shrink_element = element.clone();
+
+    Strategy::new(
+        move |rng| {
+            let len = min_len + rng.index(max_len - min_len + 1);
+            (0..len).map(|_| element.generate(rng)).collect()
+        },
+        move |vec: &Vec::<T>| {
+            let mut smaller = Vec::<Vec::<T>>::new();
+
+            // Cutting it in half first, then taking elements out one at a time
+            if vec.len() / 2 >= min_len && vec.len() > 1 {
+                smaller.push(vec[..vec.len() / 2].to_vec());
+                smaller.push(vec[vec.len() / 2..].to_vec());
+            }
+            if vec.len() > min_len {
+                for i in 0..vec.len() {
+                    let mut removed = vec.clone();
+                    removed.remove(i);
+                    smaller.push(removed);
+                }
+            }
+
+            for (i, item) in vec.iter().enumerate() {
+                for shrunk in shrink_element.shrink(item) {
+                    let mut replaced = vec.clone();
+                    replaced[i] = shrunk;
+                    smaller.push(replaced);
+                }
+            }
+
+            smaller
+        },
+    )
+}
+
+// Two values made independently, shrinking one at a time
+pub fn pairs<A: Clone + 'static, B: Clone + 'static>(
+    first: Strategy<A>, second: Strategy<B>
+) -> Strategy<(A, B)> {
+    let first = Rc::new(first);
+    let second = Rc::new(second);
+    let (shrink_first, shrink_second) = (first.clone(), second.clone());
+
+    Strategy::new(
+        move |rng| (first.generate(rng), second.generate(rng)),
+        move |(a, b): &(A, B)| {
+            let mut smaller: Vec::<(A, B)> =
+                shrink_first.shrink(a).into_iter().map(|a| (a, b.clone())).collect();
+            smaller.extend(shrink_second.shrink(b).into_iter().map(|b| (a.clone(), b)));
+            smaller
+        },
+    )
+}
+
+// Runs the property on cases random values, returning the smallest failing
+// value we can find if it ever fails. A property that panics has failed.
+pub fn find_counterexample<T: Clone>(
+    seed: u64, cases: usize, strategy: &Strategy<T>, property: impl Fn(&T) -> bool
+) -> Option<T> {
+    let fails = |value: &T| !panic::catch_unwind(AssertUnwindSafe(|| property(value))).unwrap_or(false);
+
+    let mut rng = Rng::new(seed);
+    let mut failing = (0..cases).map(|_| strategy.generate(&mut rng)).find(|value| fails(value))?;
+
+    // Keep taking the first smaller value that still fails until none of them do
+    while let Some(smaller) = strategy.shrink(&failing).into_iter().find(|value| fails(value)) {
+        failing = smaller;
+    }
+
+    Some(failing)
+}
+
+// Checks a property in a test, panicking with the smallest counterexample.
+// The seed can be changed with AOC_PROPERTY_SEED to look further afield.
+#[track_caller]
+pub fn check<T: Clone + Debug>(cases: usize, strategy: &Strategy<T>, property: impl Fn(&T) -> bool) {
+    let seed = env::var("AOC_PROPERTY_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(0);
+
+    if let Some(counterexample) = find_counterexample(seed, cases, strategy, property) {
+        panic!("property failed with seed {} for {:?}", seed, counterexample);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_stay_in_range_and_shrink_towards_the_start() {
+        let strategy = numbers(5..=9);
+        let mut rng = Rng::new(0);
+
+        assert!((0..100).all(|_| (5..=9).contains(&strategy.generate(&mut rng))));
+        assert_eq!(strategy.shrink(&9), vec![5, 7, 8]);
+        assert_eq!(strategy.shrink(&5), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn numbers_can_cover_the_whole_of_u64() {
+        let strategy = numbers(0..=u64::MAX);
+        let mut rng = Rng::new(0);
+
+        assert!((0..100).any(|_| strategy.generate(&mut rng) > u32::MAX as u64));
+    }
+
+    #[test]
+    fn failing_vecs_shrink_to_the_smallest_counterexample() {
+        let strategy = vecs(numbers(0..=100), 0..=20);
+        let counterexample = find_counterexample(0, 1000, &strategy, |v| v.iter().all(|n| *n < 50));
+
+        assert_eq!(counterexample, Some(vec![50]));
+    }
+
+    #[test]
+    fn panics_count_as_failures() {
+        let strategy = vecs(numbers(0..=10), 0..=5);
+        let counterexample = find_counterexample(0, 100, &strategy, |v| v[0] < 100);
+
+        assert_eq!(counterexample, Some(vec![]));
+    }
+
+    #[test]
+    fn pairs_shrink_both_halves() {
+        let strategy = pairs(numbers(0..=1000), numbers(0..=1000));
+        let counterexample = find_counterexample(0, 1000, &strategy, |(a, b)| a + b < 300);
+
+        assert_eq!(counterexample.map(|(a, b)| a + b), Some(300));
+    }
+
+    #[test]
+    fn passing_properties_have_no_counterexample() {
+        let strategy = vecs(numbers(0..=10), 0..=5);
+        assert_eq!(find_counterexample(0, 100, &strategy, |v| v.len() <= 5), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "property failed with seed 0 for [3]")]
+    fn check_reports_the_counterexample() {
+        check(100, &vecs(numbers(0..=10), 0..=5), |v| v.iter().all(|n| *n < 3));
+    }
+}
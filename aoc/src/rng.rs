@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+// A small deterministic random number generator (splitmix64), so that the
+// same seed always gives the same generated input on every machine without
+// needing the rand crate. It's nowhere near good enough for anything that
+// needs real randomness, but it's plenty for making up puzzle inputs.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // A number in the range, which must not be empty. The modulo makes the
+    // low numbers very slightly more likely, which doesn't matter here.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "can't pick from an empty range");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    // An index into something of length len
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..len as u64) as usize
+    }
+
+    // true with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        let fraction = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        fraction < probability
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        // Fisher-Yates
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_gives_the_same_numbers() {
+        let first: Vec::<u64> = (0..5).scan(Rng::new(7), |rng, _| Some(rng.next_u64())).collect();
+        let second: Vec::<u64> = (0..5).scan(Rng::new(7), |rng, _| Some(rng.next_u64())).collect();
+        let other: Vec::<u64> = (0..5).scan(Rng::new(8), |rng, _| Some(rng.next_u64())).collect();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn ranges_stay_in_bounds() {
+        let mut rng = Rng::new(1);
+
+        for _ in 0..1000 {
+            assert!((10..13).contains(&rng.range(10..13)));
+        }
+    }
+
+    #[test]
+    fn shuffling_keeps_every_item() {
+        let mut rng = Rng::new(3);
+        let mut items: Vec::<u32> = (0..20).collect();
+
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+}
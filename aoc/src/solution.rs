@@ -0,0 +1,205 @@
+use std::fmt;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use crate::{trace, Answer, Details, Image, ParseError, Variant};
+
+// Which half of a day's puzzle to run
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    // Whether this part should run given the part picked on the command line,
+    // where None means we run both of them
+    pub fn selected_by(self, part: Option<Part>) -> bool {
+        part.is_none_or(|p| p == self)
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+// Every day implements this. The input is parsed once, failing with a
+// ParseError if it's malformed, and then both parts are solved from the parsed
+// input, returning None if the input doesn't have an answer (e.g. the CO2
+// scrubber rating in day 3 can't always be determined).
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Option<Answer>;
+    fn part2(input: &Self::Input) -> Option<Answer>;
+
+    // Named values worked out on the way to each part's answer, e.g. the
+    // gamma and epsilon rates in day 3, which are shown alongside the answer.
+    // These aren't needed for the answer itself so they're only worked out
+    // when asked for.
+    fn part1_details(_input: &Self::Input) -> Details {
+        Vec::new()
+    }
+
+    fn part2_details(_input: &Self::Input) -> Details {
+        Vec::new()
+    }
+
+    // A picture of the puzzle's state, for the days where there's something
+    // worth looking at. step is for days that change over time, and picks
+    // which step of the simulation to draw.
+    fn render(_input: &Self::Input, _step: Option<u32>) -> Option<Image> {
+        None
+    }
+
+    // Every step of the days that play out over time, drawn for the terminal
+    // with ANSI colours. Days without an animation have no frames.
+    fn frames(_input: &Self::Input) -> Frames {
+        Box::new(std::iter::empty())
+    }
+
+    // Other ways of working out the parts, to cross-check the answers with
+    fn variants() -> Vec::<Variant<Self::Input>> {
+        Vec::new()
+    }
+}
+
+// The frames of an animation, drawn one at a time as they're asked for so
+// that a long animation of a big input never has to be held all at once
+pub type Frames = Box<dyn Iterator<Item = String>>;
+
+// The answer to one part, along with the details of how we got there
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Option<Answer>,
+    pub details: Details,
+}
+
+// Parses the input and solves the selected parts (both if part is None).
+//
+// This is a plain generic function so that the runner can keep a table of
+// `solve::<DayXX>` function pointers rather than needing a trait object.
+pub fn solve<S: Solution>(input: &str, part: Option<Part>) -> Result<Vec::<PartResult>, ParseError> {
+    let parsed = parse::<S>(input)?;
+
+    Ok(solve_parsed::<S>(&parsed, part))
+}
+
+// The results of `solve`, along with how long the parsing and the solving
+// took, for the runner's summary of every day
+pub struct Timed {
+    pub results: Vec::<PartResult>,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+pub fn solve_timed<S: Solution>(input: &str, part: Option<Part>) -> Result<Timed, ParseError> {
+    let start = Instant::now();
+    let parsed = parse::<S>(input)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let results = solve_parsed::<S>(&parsed, part);
+    let solve = start.elapsed();
+
+    Ok(Timed { results, parse, solve })
+}
+
+// Each phase of a run is a span, so that -v shows how long each of them took
+fn parse<S: Solution>(input: &str) -> Result<S::Input, ParseError> {
+    let _span = trace::span("parse");
+    S::parse(input)
+}
+
+fn solve_parsed<S: Solution>(parsed: &S::Input, part: Option<Part>) -> Vec::<PartResult> {
+    let mut results = Vec::new();
+    if Part::One.selected_by(part) {
+        let _span = trace::span("part 1");
+        results.push(PartResult {
+            part: Part::One,
+            answer: S::part1(parsed),
+            details: S::part1_details(parsed),
+        });
+    }
+    if Part::Two.selected_by(part) {
+        let _span = trace::span("part 2");
+        results.push(PartResult {
+            part: Part::Two,
+            answer: S::part2(parsed),
+            details: S::part2_details(parsed),
+        });
+    }
+
+    results
+}
+
+// Parses the input and draws it, or None if the day can't be drawn
+pub fn render<S: Solution>(input: &str, step: Option<u32>) -> Result<Option<Image>, ParseError> {
+    let parsed = parse::<S>(input)?;
+
+    Ok(S::render(&parsed, step))
+}
+
+// Parses the input and gets ready to draw its animation
+pub fn animate<S: Solution>(input: &str) -> Result<Frames, ParseError> {
+    let parsed = parse::<S>(input)?;
+
+    Ok(S::frames(&parsed))
+}
+
+// Used by each day's tests to check the answers to both parts of an input
+#[track_caller]
+pub fn assert_answers<S: Solution>(input: &str, part1: impl Into<Answer>, part2: impl Into<Answer>) {
+    let parsed = S::parse(input).unwrap_or_else(|e| panic!("Failed to parse input: {}", e));
+
+    assert_eq!(S::part1(&parsed), Some(part1.into()), "wrong answer to part 1");
+    assert_eq!(S::part2(&parsed), Some(part2.into()), "wrong answer to part 2");
+}
+
+// Prints the answers from `solve`, used by each day's binary and the runner
+pub fn print_answers(results: &[PartResult]) {
+    for result in results {
+        match &result.answer {
+            Some(answer) => println!("Part {}: {}", result.part, answer),
+            None => println!("Part {}: no answer for this input", result.part),
+        }
+
+        for (name, value) in &result.details {
+            println!("    {} = {}", name, value);
+        }
+    }
+}
+
+// Entry point for each day's own binary, which solves both parts of the file
+// and prints the answers, or prints why the file couldn't be solved
+pub fn run_file<S: Solution>(filepath: &str) -> ExitCode {
+    let input = match crate::get_file_input(filepath) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", filepath, e);
+            return ExitCode::FAILURE
+        }
+    };
+
+    match solve::<S>(&input, None) {
+        Ok(answers) => {
+            print_answers(&answers);
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("{}", e.with_file(filepath));
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::Colour;
+
+// Helpers for drawing animation frames with ANSI escape codes. Every painted
+// piece of text resets the style afterwards, so they can be freely mixed.
+const RESET: &str = "\x1b[0m";
+
+// Text in a 24-bit colour
+pub fn paint(text: &str, colour: Colour) -> String {
+    let [r, g, b] = colour;
+    format!("\x1b[38;2;{};{};{}m{}{}", r, g, b, text, RESET)
+}
+
+// Bold text in a 24-bit colour on a 24-bit background
+pub fn highlight(text: &str, colour: Colour, background: Colour) -> String {
+    let [r, g, b] = colour;
+    let [br, bg, bb] = background;
+    format!("\x1b[1;38;2;{};{};{};48;2;{};{};{}m{}{}", r, g, b, br, bg, bb, text, RESET)
+}
+
+// Takes the escape codes back out of a frame, so that tests can check what
+// it says without caring how it's coloured
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Every code we write is ESC [ ... m
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn painted_text_resets_afterwards() {
+        assert_eq!(paint("9", [255, 0, 10]), "\x1b[38;2;255;0;10m9\x1b[0m");
+        assert!(highlight("0", [0; 3], [255; 3]).ends_with("0\x1b[0m"));
+    }
+
+    #[test]
+    fn stripping_leaves_just_the_text() {
+        let frame = format!("{} {}\n", paint("12", [1, 2, 3]), highlight("7", [0; 3], [9; 3]));
+        assert_eq!(strip_ansi(&frame), "12 7\n");
+    }
+}
@@ -0,0 +1,128 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Instant;
+
+// A little tracing for working out what a day is up to without sprinkling
+// println!s through it. Spans time the phases of a run (parsing and each
+// part), and events are what the days log along the way, like each bingo
+// number drawn. Everything goes to stderr, so the answers on stdout are left
+// alone.
+//
+//   0       nothing, the default
+//   1 (-v)  how long each span took
+//   2 (-vv) the start of each span and every event, indented by span
+//
+// An event's message is only made when it's going to be shown, so the days
+// can log in their hot loops without slowing down a normal run.
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+// How much is shown in spans and events
+pub const SPANS: u8 = 1;
+pub const EVENTS: u8 = 2;
+
+thread_local! {
+    // How many spans we're inside, for the indent
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    // While `capture` is running, its verbosity and the lines it's collected
+    static CAPTURED: RefCell<Option<(u8, Vec::<String>)>> = const { RefCell::new(None) };
+}
+
+pub fn set_verbosity(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+pub fn enabled(level: u8) -> bool {
+    let captured = CAPTURED.with(|captured| captured.borrow().as_ref().map(|(verbosity, _)| *verbosity));
+    captured.unwrap_or_else(|| VERBOSITY.load(Ordering::Relaxed)) >= level
+}
+
+fn emit(message: &str) {
+    let line = format!("{}{}", "  ".repeat(DEPTH.with(Cell::get)), message);
+
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some((_, lines)) => lines.push(line),
+        None => eprintln!("{}", line),
+    });
+}
+
+// Logs something that happened, at -vv
+pub fn event(message: impl FnOnce() -> String) {
+    if enabled(EVENTS) {
+        emit(&message());
+    }
+}
+
+// Times everything up until it's dropped, with the events inside it indented
+pub struct Span {
+    name: String,
+    start: Instant,
+}
+
+pub fn span(name: &str) -> Option<Span> {
+    if !enabled(SPANS) {
+        return None
+    }
+
+    if enabled(EVENTS) {
+        emit(name);
+    }
+
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    Some(Span { name: name.to_string(), start: Instant::now() })
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+        emit(&format!("{} took {:?}", self.name, self.start.elapsed()));
+    }
+}
+
+// Runs f as if at the given verbosity, giving the lines that would have been
+// written to stderr. Only affects this thread, so tests can run side by side.
+pub fn capture<T>(verbosity: u8, f: impl FnOnce() -> T) -> (T, Vec::<String>) {
+    let outer = CAPTURED.with(|captured| captured.replace(Some((verbosity, Vec::new()))));
+    let result = f();
+    let (_, lines) = CAPTURED.with(|captured| captured.replace(outer)).unwrap();
+
+    (result, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_indented_by_span() {
+        let ((), lines) = capture(EVENTS, || {
+            let _outer = span("parse");
+            event(|| String::from("read 3 lines"));
+        });
+
+        assert_eq!(lines[..2], ["parse", "  read 3 lines"]);
+        assert!(lines[2].starts_with("parse took "), "{:?}", lines);
+    }
+
+    #[test]
+    fn only_span_timings_are_shown_at_the_first_level() {
+        let ((), lines) = capture(SPANS, || {
+            let _span = span("part 1");
+            event(|| panic!("the message shouldn't be made"));
+        });
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("part 1 took "));
+    }
+
+    #[test]
+    fn nothing_is_shown_by_default() {
+        let ((), lines) = capture(0, || {
+            let _span = span("part 2");
+            event(|| String::from("flash"));
+        });
+
+        assert!(lines.is_empty());
+    }
+}
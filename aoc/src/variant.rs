@@ -0,0 +1,131 @@
+use crate::{Answer, ParseError, Part, Solution};
+
+// Another way of working out one of the parts, usually the straightforward
+// version of a shortcut that the main solution takes (simulating every fish
+// rather than counting them by timer). The runner can check that they agree.
+pub struct Variant<I> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&I) -> Option<Answer>,
+    // Roughly how many steps solve takes for the input. The straightforward
+    // versions can take far too long on a big generated input, so they're
+    // skipped when this is over MAX_WORK.
+    pub work: fn(&I) -> u128,
+}
+
+// About as much as a variant can do in a second or so
+pub const MAX_WORK: u128 = 1_000_000_000;
+
+// What one variant made of an input, next to the main solution's answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossCheck {
+    pub variant: &'static str,
+    pub part: Part,
+    pub expected: Option<Answer>,
+    pub actual: Option<Answer>,
+    // The input was too big for the variant, so it wasn't run
+    pub skipped: bool,
+}
+
+// A skipped check neither agrees nor disagrees
+impl CrossCheck {
+    pub fn agrees(&self) -> bool {
+        !self.skipped && self.expected == self.actual
+    }
+
+    pub fn disagrees(&self) -> bool {
+        !self.skipped && self.expected != self.actual
+    }
+}
+
+// Parses the input and runs every variant of the selected parts (both if
+// part is None) alongside the main solution, apart from the ones the input is
+// too big for
+pub fn cross_check<S: Solution>(input: &str, part: Option<Part>) -> Result<Vec::<CrossCheck>, ParseError> {
+    let parsed = S::parse(input)?;
+
+    let checks = S::variants()
+        .into_iter()
+        .filter(|variant| variant.part.selected_by(part))
+        .map(|variant| {
+            let skipped = (variant.work)(&parsed) > MAX_WORK;
+
+            CrossCheck {
+                variant: variant.name,
+                part: variant.part,
+                expected: match variant.part {
+                    Part::One => S::part1(&parsed),
+                    Part::Two => S::part2(&parsed),
+                },
+                actual: if skipped { None } else { (variant.solve)(&parsed) },
+                skipped,
+            }
+        })
+        .collect();
+
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec::<u32>;
+
+        fn parse(input: &str) -> Result<Vec::<u32>, ParseError> {
+            input.split(',').map(|n| crate::parse_token(0, input, n, "a number")).collect()
+        }
+
+        fn part1(input: &Vec::<u32>) -> Option<Answer> {
+            Some(input.iter().sum::<u32>().into())
+        }
+
+        // Forgets the first number
+        fn part2(input: &Vec::<u32>) -> Option<Answer> {
+            Some(input[1..].iter().sum::<u32>().into())
+        }
+
+        fn variants() -> Vec::<Variant<Vec::<u32>>> {
+            let by_hand: fn(&Vec::<u32>) -> Option<Answer> = |input| {
+                let mut total = 0;
+                for n in input {
+                    total += n;
+                }
+                Some(total.into())
+            };
+
+            let work: fn(&Vec::<u32>) -> u128 = |input| input.iter().map(|n| *n as u128).sum();
+
+            vec![
+                Variant { name: "by hand", part: Part::One, solve: by_hand, work },
+                Variant { name: "by hand", part: Part::Two, solve: by_hand, work },
+            ]
+        }
+    }
+
+    #[test]
+    fn disagreements_are_found() {
+        let checks = cross_check::<Sum>("1,2,3", None).unwrap();
+
+        assert_eq!(checks.iter().map(|c| c.agrees()).collect::<Vec<_>>(), vec![true, false]);
+        assert_eq!((checks[1].expected.clone(), checks[1].actual.clone()), (Some(5.into()), Some(6.into())));
+    }
+
+    #[test]
+    fn only_the_selected_part_is_checked() {
+        let checks = cross_check::<Sum>("1,2,3", Some(Part::Two)).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].part, Part::Two);
+    }
+
+    #[test]
+    fn variants_are_skipped_when_the_input_is_too_big() {
+        let checks = cross_check::<Sum>("1,2,4000000000", None).unwrap();
+
+        assert!(checks.iter().all(|c| c.skipped && c.actual.is_none()));
+        assert!(checks.iter().all(|c| !c.agrees() && !c.disagrees()));
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+// Moves the cursor home and clears the screen, so each frame is drawn over the
+// last one
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+// Plays the frames in the terminal, either one every delay or one per
+// keypress when stepping through them. Each frame is drawn as it's shown, so
+// how many there are isn't known until the end.
+pub fn play(frames: impl Iterator<Item = String>, delay: Duration, step_through: bool) -> Result<(), String> {
+    let keys = step_through.then(Keys::new);
+    let mut stdout = io::stdout().lock();
+
+    let prompt = if step_through { ", press a key for the next frame or q to stop" } else { "" };
+
+    for (i, frame) in frames.enumerate() {
+        write!(stdout, "{}{}\nFrame {}{}\n", CLEAR_SCREEN, frame, i + 1, prompt)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("Failed to draw frame: {}", e))?;
+
+        match &keys {
+            // Stop when asked to, or when there are no more keys to wait for
+            Some(keys) => {
+                if matches!(keys.wait(), None | Some(b'q')) {
+                    break
+                }
+            },
+            None => thread::sleep(delay),
+        }
+    }
+
+    Ok(())
+}
+
+// Reads single keypresses from the terminal. There's no way to turn off line
+// buffering without a dependency, so we ask stty to do it for us, and put it
+// back when we're done. If that doesn't work (e.g. stdin isn't a terminal) we
+// still work, the keypress just has to be followed by enter.
+struct Keys {
+    raw: bool,
+}
+
+impl Keys {
+    fn new() -> Keys {
+        Keys { raw: stty(&["-icanon", "-echo", "min", "1"]) }
+    }
+
+    // Waits for a key, returning None if there's nothing left to read
+    fn wait(&self) -> Option<u8> {
+        let mut stdin = io::stdin().lock();
+        let mut key = [0; 1];
+
+        stdin.read_exact(&mut key).ok()?;
+
+        // Swallow the rest of the line so that it isn't read as more keys
+        if !self.raw && key[0] != b'\n' {
+            stdin.by_ref().bytes().find(|b| matches!(b, Ok(b'\n') | Err(_)));
+        }
+
+        Some(key[0])
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        if self.raw {
+            stty(&["icanon", "echo"]);
+        }
+    }
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
@@ -0,0 +1,621 @@
+use std::time::Duration;
+
+use aoc::{GenOptions, ImageFormat, Part};
+use ex01::{Compare, Filter};
+use ex02::Model;
+
+pub const USAGE: &str = "\
+Usage:
+    aoc [-v | -vv] COMMAND ...
+
+    aoc run --day N [--part 1|2] [--input PATH | --test] [--format text|json]
+                    [--render PATH [--step N]] [--animate [--delay MS] [--step-through]]
+    aoc run --all [--part 1|2] [--test] [--format text|json]
+    aoc run --day N --inputs DIR [--cross-check] [--part 1|2] [--format text|json]
+    aoc run --day 1 [--window K] [--compare increase|decrease|equal] [--filter FILTER...]
+                    [--input PATH | --test] [--format text|json]
+    aoc sweep [--input PATH | --test] [--windows K,K...] [--compare increase|decrease|equal]
+              [--every N] [--format text|json]
+    aoc report [--input PATH | --test] [--window K] [--csv PATH] [--format text|json]
+    aoc trajectory [--input PATH | --test] [--model plain|aim] [--deeper-than D] [--csv PATH]
+                   [--svg PATH] [--format text|json]
+    aoc verify [--day N] [--answers PATH]
+    aoc bench [--day N] [--iterations K] [--input PATH | --test] [--history PATH]
+    aoc gen --day N [--seed S] [--size N] [--width N] [--height N] [--bits N] [--range N]
+    aoc fuzz [--day N] [--iterations K] [--seed S] [--timeout MS] [--max-len N] [--corpus DIR]
+
+Options:
+    -v, -vv         show how long parsing and each part took on stderr, and with -vv
+                    also what each day is doing along the way
+    --day N         which day to run
+    --all           run every day at once and show a summary of them
+    --part 1|2      only run one part of the day, by default both are run
+    --input PATH    read the puzzle input from PATH, or from stdin if PATH is -
+    --test          run against the day's test_input.txt instead of input.txt
+    --inputs DIR    run against every file in DIR, with a row for each of them
+    --cross-check   also solve each input in every other way the day knows, and
+                    flag any input where they disagree. Ways that would take too long on
+                    a big input are skipped
+    --window K      for day 1, count how many windows of K depths compare to the window
+                    before them, rather than solving the parts, by default 1
+    --compare OP    how the windows should compare: increase (the default), decrease or equal
+    --filter FILTER for day 1, also count the windows after smoothing the depths with median:K
+                    or mean:K over windows of K depths, or hysteresis:N to ignore changes of
+                    N or less. Filters can be given more than once or as a list like
+                    median:3,hysteresis:2, and are applied in order
+    --windows K,K   for sweep, the window sizes to count at once, by default 1,3
+    --every N       for sweep, print the running counts every N depths, by default 1000
+    --window K      for report, how many depths to average over for each windowed mean,
+                    by default 3
+    --csv PATH      for report, write the windowed means to PATH as CSV, or print them
+                    instead of the report if PATH is -
+    --model MODEL   for trajectory, how day 2's moves are understood: plain (the default)
+                    as in part 1, or aim as in part 2. The input can be a pilot script
+    --deeper-than D for trajectory, also find the first move that goes deeper than D
+    --csv PATH      for trajectory, write the position after every move to PATH as CSV,
+                    or print it instead of the summary if PATH is -
+    --svg PATH      for trajectory, draw the path to PATH as an SVG, or print it if PATH is -
+    --format FORMAT print the answers as text (the default) or as json
+    --render PATH   also draw the day to PATH, which can be a .pgm, .ppm or .svg
+    --step N        for days that change over time, which step to draw, by default 0
+    --animate       play the day out step by step in the terminal before the answers
+    --delay MS      how long to show each frame of the animation for, by default 100
+    --step-through  wait for a keypress between frames instead, q stops the animation
+    --answers PATH  the answers file to verify against, by default answers.toml
+    --iterations K  how many times to run each phase when benchmarking, by default 100
+    --history PATH  the file to append benchmark results to, by default bench_history.jsonl
+    --seed S        the seed for a generated input, by default 0
+    --size N        how many lines, cards, fish etc. to generate
+    --width N       the width of a generated grid
+    --height N      the height of a generated grid
+    --bits N        how many bits long each generated diagnostic number is
+    --range N       how big generated coordinates, positions or bingo numbers can be
+    --timeout MS    how long a fuzzed input can run for before it counts as a hang, by default 5000
+    --max-len N     the longest input to fuzz with, by default 4096 bytes
+    --corpus DIR    where to keep each day's fuzzing corpus and crashes, by default fuzz/
+
+The generated input is written to stdout, and its answers to stderr when the
+generator can work them out. When fuzzing, --iterations is how many inputs to
+try for each day, by default 10000.";
+
+pub enum Command {
+    Run(RunArgs),
+    RunAll(RunAllArgs),
+    RunInputs(RunInputsArgs),
+    RunWindows(RunWindowsArgs),
+    Sweep(SweepArgs),
+    Report(ReportArgs),
+    Trajectory(TrajectoryArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Gen(GenArgs),
+    Fuzz(FuzzArgs),
+}
+
+// Where a day should read its input from
+pub enum InputSource {
+    Real,
+    Test,
+    Path(String),
+}
+
+pub enum Format {
+    Text,
+    Json,
+}
+
+pub struct RunArgs {
+    pub day: u8,
+    pub part: Option<Part>,
+    pub input: InputSource,
+    pub format: Format,
+    pub render: Option<(String, ImageFormat)>,
+    pub step: Option<u32>,
+    pub animate: Option<Animation>,
+}
+
+pub struct RunAllArgs {
+    pub part: Option<Part>,
+    pub input: InputSource,
+    pub format: Format,
+}
+
+pub struct RunInputsArgs {
+    pub day: u8,
+    pub part: Option<Part>,
+    pub dir: String,
+    pub format: Format,
+    pub cross_check: bool,
+}
+
+pub struct RunWindowsArgs {
+    pub input: InputSource,
+    pub format: Format,
+    pub window: usize,
+    pub compare: Compare,
+    pub filters: Vec::<Filter>,
+}
+
+pub struct SweepArgs {
+    pub input: InputSource,
+    pub windows: Vec::<usize>,
+    pub compare: Compare,
+    pub every: usize,
+    pub format: Format,
+}
+
+pub struct ReportArgs {
+    pub input: InputSource,
+    pub window: usize,
+    pub csv: Option<String>,
+    pub format: Format,
+}
+
+pub struct TrajectoryArgs {
+    pub input: InputSource,
+    pub model: Model,
+    pub deeper_than: Option<i64>,
+    pub csv: Option<String>,
+    pub svg: Option<String>,
+    pub format: Format,
+}
+
+pub struct Animation {
+    pub delay: Duration,
+    pub step_through: bool,
+}
+
+pub struct VerifyArgs {
+    pub day: Option<u8>,
+    pub answers: Option<String>,
+}
+
+pub struct BenchArgs {
+    pub day: Option<u8>,
+    pub iterations: usize,
+    pub input: InputSource,
+    pub history: Option<String>,
+}
+
+pub struct GenArgs {
+    pub day: u8,
+    pub seed: u64,
+    pub options: GenOptions,
+}
+
+pub struct FuzzArgs {
+    pub day: Option<u8>,
+    pub iterations: usize,
+    pub seed: u64,
+    pub timeout: Duration,
+    pub max_len: usize,
+    pub corpus: Option<String>,
+}
+
+// -v and -vv can go anywhere, so they're taken out before the rest of the
+// arguments are parsed
+pub fn take_verbosity(args: &[String]) -> (u8, Vec::<String>) {
+    let mut verbosity = 0;
+    let mut rest = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            _ => rest.push(arg.clone()),
+        }
+    }
+
+    (verbosity, rest)
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("run") => parse_run_args(&args[1..]),
+        Some("sweep") => Ok(Command::Sweep(parse_sweep_args(&args[1..])?)),
+        Some("report") => Ok(Command::Report(parse_report_args(&args[1..])?)),
+        Some("trajectory") => Ok(Command::Trajectory(parse_trajectory_args(&args[1..])?)),
+        Some("verify") => Ok(Command::Verify(parse_verify_args(&args[1..])?)),
+        Some("bench") => Ok(Command::Bench(parse_bench_args(&args[1..])?)),
+        Some("gen") => Ok(Command::Gen(parse_gen_args(&args[1..])?)),
+        Some("fuzz") => Ok(Command::Fuzz(parse_fuzz_args(&args[1..])?)),
+        Some(command) => Err(format!("Unknown command {}", command)),
+        None => Err(String::from("No command given")),
+    }
+}
+
+fn parse_run_args(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut inputs = None;
+    let mut cross_check = false;
+    let mut part = None;
+    let mut input = InputSource::Real;
+    let mut format = Format::Text;
+    let mut render = None;
+    let mut step = None;
+    let mut animate = false;
+    let mut delay = None;
+    let mut step_through = false;
+    let mut window = None;
+    let mut compare = None;
+    let mut filters = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(next_value(arg, args.next())?)?),
+            "--all" => all = true,
+            "--part" => part = Some(parse_part(next_value(arg, args.next())?)?),
+            "--input" => input = InputSource::Path(next_value(arg, args.next())?.to_string()),
+            "--test" => input = InputSource::Test,
+            "--inputs" => inputs = Some(next_value(arg, args.next())?.to_string()),
+            "--cross-check" => cross_check = true,
+            "--format" => format = parse_format(next_value(arg, args.next())?)?,
+            "--render" => render = Some(parse_render(next_value(arg, args.next())?)?),
+            "--step" => step = Some(parse_step(next_value(arg, args.next())?)?),
+            "--animate" => animate = true,
+            "--delay" => delay = Some(parse_delay(next_value(arg, args.next())?)?),
+            "--step-through" => step_through = true,
+            "--window" => window = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--compare" => compare = Some(parse_compare(next_value(arg, args.next())?)?),
+            "--filter" => filters.extend(parse_filters(next_value(arg, args.next())?)?),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if cross_check && inputs.is_none() {
+        return Err(String::from("--cross-check needs --inputs"))
+    }
+
+    // Drawing the day only makes sense when running it on a single input
+    let drawing = render.is_some() || step.is_some() || animate || delay.is_some() || step_through;
+    let drawing_flags = "--render, --step, --animate, --delay or --step-through";
+
+    if all {
+        if day.is_some() || inputs.is_some() {
+            return Err(String::from("--all can't be used with --day or --inputs"))
+        }
+
+        // Every day needs its own input, so only the real and test inputs make sense
+        if matches!(input, InputSource::Path(_)) {
+            return Err(String::from("--all can't be used with --input"))
+        }
+
+        if drawing {
+            return Err(format!("--all can't be used with {}", drawing_flags))
+        }
+
+        if window.is_some() || compare.is_some() || !filters.is_empty() {
+            return Err(String::from("--all can't be used with --window, --compare or --filter"))
+        }
+
+        return Ok(Command::RunAll(RunAllArgs { part, input, format }))
+    }
+
+    let day = day.ok_or("Missing --day")?;
+
+    if window.is_some() || compare.is_some() || !filters.is_empty() {
+        if day != 1 {
+            return Err(String::from("--window, --compare and --filter are only for day 1"))
+        }
+
+        if part.is_some() || inputs.is_some() || drawing {
+            let others = format!("--part, --inputs, {}", drawing_flags);
+            return Err(format!("--window, --compare and --filter can't be used with {}", others))
+        }
+
+        let window = window.unwrap_or(1);
+        let compare = compare.unwrap_or(Compare::Increase);
+
+        return Ok(Command::RunWindows(RunWindowsArgs { input, format, window, compare, filters }))
+    }
+
+    if let Some(dir) = inputs {
+        if !matches!(input, InputSource::Real) {
+            return Err(String::from("--inputs can't be used with --input or --test"))
+        }
+
+        if drawing {
+            return Err(format!("--inputs can't be used with {}", drawing_flags))
+        }
+
+        return Ok(Command::RunInputs(RunInputsArgs { day, part, dir, format, cross_check }))
+    }
+
+    if step.is_some() && render.is_none() {
+        return Err(String::from("--step needs --render"))
+    }
+
+    if (delay.is_some() || step_through) && !animate {
+        return Err(String::from("--delay and --step-through need --animate"))
+    }
+
+    // The frames would end up in the middle of the json
+    if animate && matches!(format, Format::Json) {
+        return Err(String::from("--animate can't be used with --format json"))
+    }
+
+    // Keypresses are read from stdin, so the input can't come from there too
+    if step_through && matches!(&input, InputSource::Path(path) if path == "-") {
+        return Err(String::from("--step-through can't be used when the input is read from stdin"))
+    }
+
+    let animate = animate.then(|| Animation {
+        delay: delay.unwrap_or(Duration::from_millis(100)),
+        step_through,
+    });
+
+    Ok(Command::Run(RunArgs { day, part, input, format, render, step, animate }))
+}
+
+fn parse_sweep_args(args: &[String]) -> Result<SweepArgs, String> {
+    let mut input = InputSource::Real;
+    let mut windows = vec![1, 3];
+    let mut compare = Compare::Increase;
+    let mut every = 1000;
+    let mut format = Format::Text;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = InputSource::Path(next_value(arg, args.next())?.to_string()),
+            "--test" => input = InputSource::Test,
+            "--windows" => windows = parse_windows(next_value(arg, args.next())?)?,
+            "--compare" => compare = parse_compare(next_value(arg, args.next())?)?,
+            "--every" => every = parse_number(arg, next_value(arg, args.next())?)?,
+            "--format" => format = parse_format(next_value(arg, args.next())?)?,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if every == 0 {
+        return Err(String::from("--every needs to be at least 1"))
+    }
+
+    Ok(SweepArgs { input, windows, compare, every, format })
+}
+
+fn parse_report_args(args: &[String]) -> Result<ReportArgs, String> {
+    let mut input = InputSource::Real;
+    let mut window = 3;
+    let mut csv = None;
+    let mut format = Format::Text;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = InputSource::Path(next_value(arg, args.next())?.to_string()),
+            "--test" => input = InputSource::Test,
+            "--window" => window = parse_number(arg, next_value(arg, args.next())?)?,
+            "--csv" => csv = Some(next_value(arg, args.next())?.to_string()),
+            "--format" => format = parse_format(next_value(arg, args.next())?)?,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if window == 0 {
+        return Err(String::from("--window needs to be at least 1"))
+    }
+
+    Ok(ReportArgs { input, window, csv, format })
+}
+
+fn parse_trajectory_args(args: &[String]) -> Result<TrajectoryArgs, String> {
+    let mut input = InputSource::Real;
+    let mut model = Model::Plain;
+    let mut deeper_than = None;
+    let mut csv = None;
+    let mut svg = None;
+    let mut format = Format::Text;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = InputSource::Path(next_value(arg, args.next())?.to_string()),
+            "--test" => input = InputSource::Test,
+            "--model" => model = parse_model(next_value(arg, args.next())?)?,
+            "--deeper-than" => deeper_than = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--csv" => csv = Some(next_value(arg, args.next())?.to_string()),
+            "--svg" => svg = Some(next_value(arg, args.next())?.to_string()),
+            "--format" => format = parse_format(next_value(arg, args.next())?)?,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if csv.as_deref() == Some("-") && svg.as_deref() == Some("-") {
+        return Err(String::from("Only one of --csv and --svg can be printed"))
+    }
+
+    Ok(TrajectoryArgs { input, model, deeper_than, csv, svg, format })
+}
+
+fn parse_verify_args(args: &[String]) -> Result<VerifyArgs, String> {
+    let mut day = None;
+    let mut answers = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(next_value(arg, args.next())?)?),
+            "--answers" => answers = Some(next_value(arg, args.next())?.to_string()),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    Ok(VerifyArgs { day, answers })
+}
+
+fn parse_bench_args(args: &[String]) -> Result<BenchArgs, String> {
+    let mut day = None;
+    let mut iterations = 100;
+    let mut input = InputSource::Real;
+    let mut history = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(next_value(arg, args.next())?)?),
+            "--iterations" => iterations = parse_iterations(next_value(arg, args.next())?)?,
+            "--input" => input = InputSource::Path(next_value(arg, args.next())?.to_string()),
+            "--test" => input = InputSource::Test,
+            "--history" => history = Some(next_value(arg, args.next())?.to_string()),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    Ok(BenchArgs { day, iterations, input, history })
+}
+
+fn parse_gen_args(args: &[String]) -> Result<GenArgs, String> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut options = GenOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(next_value(arg, args.next())?)?),
+            "--seed" => seed = parse_number(arg, next_value(arg, args.next())?)?,
+            "--size" => options.size = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--width" => options.width = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--height" => options.height = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--bits" => options.bits = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            "--range" => options.range = Some(parse_number(arg, next_value(arg, args.next())?)?),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    let day = day.ok_or("Missing --day")?;
+
+    Ok(GenArgs { day, seed, options })
+}
+
+fn parse_fuzz_args(args: &[String]) -> Result<FuzzArgs, String> {
+    let mut day = None;
+    let mut iterations = 10_000;
+    let mut seed = 0;
+    let mut timeout = Duration::from_secs(5);
+    let mut max_len = aoc::MAX_INPUT_LEN;
+    let mut corpus = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_day(next_value(arg, args.next())?)?),
+            "--iterations" => iterations = parse_iterations(next_value(arg, args.next())?)?,
+            "--seed" => seed = parse_number(arg, next_value(arg, args.next())?)?,
+            "--timeout" => timeout = Duration::from_millis(parse_number(arg, next_value(arg, args.next())?)?),
+            "--max-len" => max_len = parse_number(arg, next_value(arg, args.next())?)?,
+            "--corpus" => corpus = Some(next_value(arg, args.next())?.to_string()),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    Ok(FuzzArgs { day, iterations, seed, timeout, max_len, corpus })
+}
+
+fn next_value<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a str, String> {
+    value
+        .map(|v| v.as_str())
+        .ok_or(format!("Missing value for {}", flag))
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    value.parse().map_err(|_| format!("Invalid day {}", value))
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid value {} for {}", value, flag))
+}
+
+fn parse_iterations(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(iterations) if iterations > 0 => Ok(iterations),
+        _ => Err(format!("Invalid number of iterations {}", value)),
+    }
+}
+
+fn parse_render(path: &str) -> Result<(String, ImageFormat), String> {
+    let format = ImageFormat::from_path(path)
+        .ok_or(format!("Can't render to {}, expected a .pgm, .ppm or .svg file", path))?;
+
+    Ok((path.to_string(), format))
+}
+
+fn parse_delay(value: &str) -> Result<Duration, String> {
+    value
+        .parse()
+        .map(Duration::from_millis)
+        .map_err(|_| format!("Invalid delay {}", value))
+}
+
+fn parse_step(value: &str) -> Result<u32, String> {
+    value.parse().map_err(|_| format!("Invalid step {}", value))
+}
+
+fn parse_format(value: &str) -> Result<Format, String> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("Invalid format {}, expected text or json", value)),
+    }
+}
+
+fn parse_windows(value: &str) -> Result<Vec::<usize>, String> {
+    let windows = value
+        .split(',')
+        .map(|window| window.trim().parse().ok().filter(|window| *window > 0))
+        .collect::<Option<Vec::<usize>>>();
+
+    windows.ok_or(format!("Invalid windows {}, expected window sizes like 1,3", value))
+}
+
+fn parse_compare(value: &str) -> Result<Compare, String> {
+    Compare::from_name(value).ok_or(format!("Invalid comparison {}, expected increase, decrease or equal", value))
+}
+
+fn parse_filters(value: &str) -> Result<Vec::<Filter>, String> {
+    value
+        .split(',')
+        .map(|spec| {
+            let expected = "median:K, mean:K or hysteresis:N";
+            Filter::from_spec(spec.trim()).ok_or(format!("Invalid filter {}, expected {}", spec, expected))
+        })
+        .collect()
+}
+
+fn parse_model(value: &str) -> Result<Model, String> {
+    Model::from_name(value).ok_or(format!("Invalid model {}, expected plain or aim", value))
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("Invalid part {}, expected 1 or 2", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &str) -> Result<Command, String> {
+        let args: Vec::<String> = args.split_whitespace().map(String::from).collect();
+        parse_run_args(&args)
+    }
+
+    #[test]
+    fn drawing_flags_are_rejected_where_nothing_is_drawn() {
+        for flags in ["--step 5", "--delay 10", "--step-through", "--render day.svg", "--animate"] {
+            for command in ["--all", "--day 1 --window 3", "--day 4 --inputs inputs"] {
+                let error = run_args(&format!("{} {}", command, flags)).err();
+                assert!(error.is_some_and(|e| e.contains("can't be used with")), "{} {}", command, flags);
+            }
+        }
+
+        assert!(run_args("--day 4 --render day.svg --step 5").is_ok());
+        assert!(run_args("--day 4 --animate --delay 10 --step-through").is_ok());
+    }
+}
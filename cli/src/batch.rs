@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use aoc::{CrossCheck, Part, Timed};
+
+use crate::args::{Format, RunInputsArgs};
+use crate::bench::format_duration;
+use crate::days::{self, Day};
+use crate::json::Json;
+use crate::panics;
+use crate::run_all::describe;
+use crate::table::Table;
+
+// How one input got on, like a day in `run --all`
+enum Outcome {
+    Solved(Timed, Vec::<CrossCheck>),
+    Failed(String),
+    Panicked(String),
+}
+
+struct InputRun {
+    input: String,
+    outcome: Outcome,
+}
+
+// Solves every file in the directory with the one day, e.g. everyone's
+// different puzzle inputs, printing a row for each file
+pub fn run_inputs(args: RunInputsArgs) -> Result<ExitCode, String> {
+    let day = days::find_day(args.day)
+        .ok_or(format!("There is no solution for day {}", args.day))?;
+
+    let files = list_files(Path::new(&args.dir))?;
+    if files.is_empty() {
+        return Err(format!("There are no inputs in {}", args.dir))
+    }
+
+    let runs: Vec::<InputRun> = panics::quietly(|| {
+        files.iter().map(|file| run_input(day, file, &args)).collect()
+    });
+
+    match args.format {
+        Format::Text => print_table(&runs, args.cross_check),
+        Format::Json => println!("{}", Json::Array(runs.iter().map(Json::from).collect())),
+    }
+
+    let all_agree = runs.iter().all(|run| {
+        matches!(&run.outcome, Outcome::Solved(_, checks) if !checks.iter().any(CrossCheck::disagrees))
+    });
+
+    Ok(if all_agree { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+// Sorted so that the rows come out in the same order every time
+fn list_files(dir: &Path) -> Result<Vec::<PathBuf>, String> {
+    let mut files: Vec::<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+
+    files.sort();
+    Ok(files)
+}
+
+fn run_input(day: &Day, file: &Path, args: &RunInputsArgs) -> InputRun {
+    let input = file.to_string_lossy().to_string();
+
+    let solve = |text: &str| -> Result<(Timed, Vec::<CrossCheck>), String> {
+        let timed = (day.solve_timed)(text, args.part).map_err(|e| e.with_file(&input).to_string())?;
+
+        let checks = if args.cross_check {
+            (day.cross_check)(text, args.part).map_err(|e| e.with_file(&input).to_string())?
+        } else {
+            Vec::new()
+        };
+
+        Ok((timed, checks))
+    };
+
+    let outcome = match aoc::get_file_input(&input) {
+        Err(e) => Outcome::Failed(format!("Failed to read {}: {}", input, e)),
+        Ok(text) => match panics::catch(|| solve(&text)) {
+            Ok(Ok((timed, checks))) => Outcome::Solved(timed, checks),
+            Ok(Err(error)) => Outcome::Failed(error),
+            Err(message) => Outcome::Panicked(message),
+        },
+    };
+
+    InputRun { input, outcome }
+}
+
+fn print_table(runs: &[InputRun], cross_check: bool) {
+    let mut table = Table::new(&["input", "part 1", "part 2", "time", "status"]);
+
+    for run in runs {
+        let row = match &run.outcome {
+            Outcome::Solved(timed, checks) => vec![
+                describe(&timed.results, Part::One),
+                describe(&timed.results, Part::Two),
+                format_duration(timed.parse + timed.solve),
+                status(checks, cross_check),
+            ],
+            Outcome::Failed(error) | Outcome::Panicked(error) => vec![
+                String::from("-"),
+                String::from("-"),
+                String::from("-"),
+                error.clone(),
+            ],
+        };
+
+        table.add_row([vec![run.input.clone()], row].concat());
+    }
+
+    table.print();
+}
+
+// Spells out every disagreement, as that's the whole point of cross-checking
+fn status(checks: &[CrossCheck], cross_check: bool) -> String {
+    let disagreements: Vec::<String> = checks
+        .iter()
+        .filter(|check| check.disagrees())
+        .map(|check| {
+            format!(
+                "part {} {} gives {}, not {}",
+                check.part, check.variant, describe_answer(&check.actual), describe_answer(&check.expected)
+            )
+        })
+        .collect();
+
+    if !disagreements.is_empty() {
+        return format!("MISMATCH: {}", disagreements.join("; "))
+    }
+
+    if !cross_check {
+        return String::from("ok")
+    }
+
+    let agreed = match checks.iter().filter(|check| check.agrees()).count() {
+        0 => None,
+        1 => Some(String::from("1 cross-check agrees")),
+        n => Some(format!("{} cross-checks agree", n)),
+    };
+
+    let skipped: Vec::<String> = checks
+        .iter()
+        .filter(|check| check.skipped)
+        .map(|check| format!("part {} {}", check.part, check.variant))
+        .collect();
+    let skipped = (!skipped.is_empty()).then(|| format!("skipped {} as the input is too big", skipped.join(", ")));
+
+    let notes: Vec::<String> = [agreed, skipped].into_iter().flatten().collect();
+    if notes.is_empty() {
+        String::from("ok (nothing to cross-check)")
+    } else {
+        format!("ok ({})", notes.join("; "))
+    }
+}
+
+fn describe_answer(answer: &Option<aoc::Answer>) -> String {
+    answer.as_ref().map_or(String::from("no answer"), |answer| answer.to_string())
+}
+
+impl From<&CrossCheck> for Json {
+    fn from(check: &CrossCheck) -> Json {
+        Json::object(vec![
+            ("variant", Json::string(check.variant)),
+            ("part", Json::Int(check.part.number() as i128)),
+            ("expected", check.expected.as_ref().map_or(Json::Null, Json::from)),
+            ("actual", check.actual.as_ref().map_or(Json::Null, Json::from)),
+            ("agrees", Json::Bool(check.agrees())),
+            ("skipped", Json::Bool(check.skipped)),
+        ])
+    }
+}
+
+impl From<&InputRun> for Json {
+    fn from(run: &InputRun) -> Json {
+        let mut fields = vec![("input", Json::string(&run.input))];
+
+        match &run.outcome {
+            Outcome::Solved(timed, checks) => {
+                let agrees = !checks.iter().any(CrossCheck::disagrees);
+                fields.push(("status", Json::string(if agrees { "ok" } else { "mismatch" })));
+                fields.push(("parse_ns", Json::Int(timed.parse.as_nanos() as i128)));
+                fields.push(("solve_ns", Json::Int(timed.solve.as_nanos() as i128)));
+                fields.push(("parts", Json::Array(timed.results.iter().map(Json::from).collect())));
+                fields.push(("cross_checks", Json::Array(checks.iter().map(Json::from).collect())));
+            },
+            Outcome::Failed(error) => {
+                fields.push(("status", Json::string("error")));
+                fields.push(("error", Json::string(error)));
+            },
+            Outcome::Panicked(message) => {
+                fields.push(("status", Json::string("panicked")));
+                fields.push(("error", Json::string(message)));
+            },
+        }
+
+        Json::object(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Answer;
+
+    fn check(expected: i32, actual: i32) -> CrossCheck {
+        CrossCheck {
+            variant: "trying every position",
+            part: Part::Two,
+            expected: Some(Answer::from(expected)),
+            actual: Some(Answer::from(actual)),
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn disagreements_are_spelled_out() {
+        assert_eq!(
+            status(&[check(5, 5), check(5, 6)], true),
+            "MISMATCH: part 2 trying every position gives 6, not 5"
+        );
+    }
+
+    #[test]
+    fn agreements_are_counted() {
+        assert_eq!(status(&[check(5, 5), check(7, 7)], true), "ok (2 cross-checks agree)");
+        assert_eq!(status(&[], true), "ok (nothing to cross-check)");
+        assert_eq!(status(&[], false), "ok");
+    }
+
+    #[test]
+    fn skipped_checks_are_named() {
+        let skipped = CrossCheck { actual: None, skipped: true, ..check(5, 5) };
+
+        assert_eq!(
+            status(&[check(5, 5), skipped.clone()], true),
+            "ok (1 cross-check agrees; skipped part 2 trying every position as the input is too big)"
+        );
+        assert_eq!(status(&[skipped], true), "ok (skipped part 2 trying every position as the input is too big)");
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aoc::Summary;
+
+use crate::args::{BenchArgs, InputSource};
+use crate::days::{self, Day};
+use crate::json::Json;
+use crate::table::Table;
+
+// Times each phase of the selected days, printing a table of the results and
+// appending them to the history file so that we can spot regressions later
+pub fn bench(args: BenchArgs) -> Result<ExitCode, String> {
+    let selected: Vec::<&Day> = match args.day {
+        Some(day) => vec![days::find_day(day).ok_or(format!("There is no solution for day {}", day))?],
+        None => days::DAYS.iter().collect(),
+    };
+
+    if matches!(args.input, InputSource::Path(_)) && args.day.is_none() {
+        return Err(String::from("--input needs a --day to go with it"))
+    }
+
+    let mut table = Table::new(&["day", "phase", "min", "median", "p95"]);
+    let mut history = Vec::<Json>::new();
+
+    for day in selected {
+        let filepath = days::input_path(day.day, &args.input);
+        let filepath = filepath.to_string_lossy();
+
+        let input = aoc::get_file_input(&filepath)
+            .map_err(|e| format!("Failed to read {}: {}", filepath, e))?;
+
+        let timings = (day.bench)(&input, args.iterations)
+            .map_err(|e| e.with_file(&filepath).to_string())?;
+
+        let phases = [("parse", &timings.parse), ("part1", &timings.part1), ("part2", &timings.part2)];
+        let mut phase_json = Vec::new();
+
+        for (phase, durations) in phases {
+            let Some(summary) = aoc::summarise(durations) else { continue };
+
+            table.add_row(vec![
+                day.day.to_string(),
+                phase.to_string(),
+                format_duration(summary.min),
+                format_duration(summary.median),
+                format_duration(summary.p95),
+            ]);
+            phase_json.push((phase, summary_json(&summary)));
+        }
+
+        history.push(Json::object(vec![
+            ("timestamp", Json::Int(unix_time() as i128)),
+            ("day", Json::Int(day.day as i128)),
+            ("input", Json::string(&filepath)),
+            ("iterations", Json::Int(args.iterations as i128)),
+            ("release", Json::Bool(!cfg!(debug_assertions))),
+            ("phases", Json::object(phase_json)),
+        ]));
+    }
+
+    table.print();
+
+    let history_path = args.history.map_or_else(
+        || days::workspace_dir().join("bench_history.jsonl"),
+        |p| p.into()
+    );
+    append_history(&history_path.to_string_lossy(), &history)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn summary_json(summary: &Summary) -> Json {
+    Json::object(vec![
+        ("min_ns", Json::Int(summary.min.as_nanos() as i128)),
+        ("median_ns", Json::Int(summary.median.as_nanos() as i128)),
+        ("p95_ns", Json::Int(summary.p95.as_nanos() as i128)),
+    ])
+}
+
+// The history is one JSON object per line, so we can keep appending to it
+fn append_history(filepath: &str, history: &[Json]) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filepath)
+        .map_err(|e| format!("Failed to open {}: {}", filepath, e))?;
+
+    for entry in history {
+        writeln!(file, "{}", entry).map_err(|e| format!("Failed to write to {}: {}", filepath, e))?;
+    }
+
+    Ok(())
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+
+    if nanos < 1_000_000.0 {
+        format!("{:.1}µs", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.2}ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2}s", nanos / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_are_shown_in_a_sensible_unit() {
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.5µs");
+        assert_eq!(format_duration(Duration::from_micros(2_345)), "2.35ms");
+        assert_eq!(format_duration(Duration::from_millis(3_000)), "3.00s");
+    }
+}
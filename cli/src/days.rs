@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use aoc::{
+    CrossCheck, Frames, GenOptions, Generated, Generator, Image, ParseError, Part, PartResult, Solution, Timed,
+    Timings,
+};
+
+use crate::args::InputSource;
+
+type SolveFn = fn(&str, Option<Part>) -> Result<Vec::<PartResult>, ParseError>;
+type SolveTimedFn = fn(&str, Option<Part>) -> Result<Timed, ParseError>;
+type CrossCheckFn = fn(&str, Option<Part>) -> Result<Vec::<CrossCheck>, ParseError>;
+type BenchFn = fn(&str, usize) -> Result<Timings, ParseError>;
+type RenderFn = fn(&str, Option<u32>) -> Result<Option<Image>, ParseError>;
+type AnimateFn = fn(&str) -> Result<Frames, ParseError>;
+type GenerateFn = fn(u64, &GenOptions) -> Generated;
+pub type FuzzFn = fn(&[u8]) -> Option<Vec::<PartResult>>;
+
+// Everything the runner can do with a day, as plain function pointers so that
+// all of the days can live in one table
+pub struct Day {
+    pub day: u8,
+    pub solve: SolveFn,
+    pub solve_timed: SolveTimedFn,
+    pub cross_check: CrossCheckFn,
+    pub bench: BenchFn,
+    pub render: RenderFn,
+    pub animate: AnimateFn,
+    pub generate: GenerateFn,
+    pub fuzz: FuzzFn,
+}
+
+const fn day<S: Solution + Generator>(day: u8) -> Day {
+    Day {
+        day,
+        solve: aoc::solve::<S>,
+        solve_timed: aoc::solve_timed::<S>,
+        cross_check: aoc::cross_check::<S>,
+        bench: aoc::bench::<S>,
+        render: aoc::render::<S>,
+        animate: aoc::animate::<S>,
+        generate: aoc::generate::<S>,
+        fuzz: aoc::fuzz_one::<S>,
+    }
+}
+
+// Every day that the runner knows about
+pub const DAYS: [Day; 11] = [
+    day::<ex01::Day01>(1),
+    day::<ex02::Day02>(2),
+    day::<ex03::Day03>(3),
+    day::<ex04::Day04>(4),
+    day::<ex05::Day05>(5),
+    day::<ex06::Day06>(6),
+    day::<ex07::Day07>(7),
+    day::<ex08::Day08>(8),
+    day::<ex09::Day09>(9),
+    day::<ex10::Day10>(10),
+    day::<ex11::Day11>(11),
+];
+
+pub fn find_day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+// We find everything relative to the workspace rather than the cwd so the
+// runner works from anywhere
+pub fn workspace_dir() -> PathBuf {
+    let cli_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    cli_dir.parent().unwrap_or(cli_dir).to_path_buf()
+}
+
+// The directory a day lives in, e.g. ex09 for day 9
+pub fn day_dir(day: u8) -> PathBuf {
+    workspace_dir().join(format!("ex{:02}", day))
+}
+
+// Where to read a day's input from, test_input.txt and input.txt live in the
+// day's directory
+pub fn input_path(day: u8, input: &InputSource) -> PathBuf {
+    match input {
+        InputSource::Real => day_dir(day).join("input.txt"),
+        InputSource::Test => day_dir(day).join("test_input.txt"),
+        InputSource::Path(path) => path.into(),
+    }
+}
+
+// Reads a day's input, giving it along with the name to use for it in errors
+pub fn read_input(day: u8, input: &InputSource) -> Result<(String, String), String> {
+    let filepath = input_path(day, input);
+    let filepath = filepath.to_string_lossy();
+
+    let input = aoc::get_file_input(&filepath)
+        .map_err(|e| format!("Failed to read {}: {}", filepath, e))?;
+
+    let name = if filepath == "-" { "<stdin>" } else { &filepath };
+
+    Ok((input, name.to_string()))
+}
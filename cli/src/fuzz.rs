@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use aoc::{PartResult, Rng};
+
+use crate::args::FuzzArgs;
+use crate::days::{self, Day, FuzzFn};
+use crate::panics;
+use crate::table::Table;
+
+// Each input runs on its own thread with a small stack, so that recursion
+// which grows with the input overflows on the fuzzer's small inputs rather
+// than waiting for a big real one
+const STACK_SIZE: usize = 256 * 1024;
+
+// Inputs that get new answers are added to the corpus so that later mutations
+// get further into the solutions, up to this many per day
+const MAX_CORPUS: usize = 256;
+
+enum Outcome {
+    Parsed(Vec::<PartResult>),
+    Rejected,
+    Panicked(String),
+    // With the thread that's still running it
+    Hung(JoinHandle<()>),
+}
+
+// Fuzzes the selected days, keeping a corpus for each day under the corpus
+// directory (fuzz/day01/corpus etc.) and writing any input that panics or
+// hangs to crashes/ next to it. A stack overflow aborts the whole process, so
+// each input is written to in-flight before it runs, which leaves behind the
+// input that did it.
+pub fn fuzz(args: FuzzArgs) -> Result<ExitCode, String> {
+    let selected: Vec::<&Day> = match args.day {
+        Some(day) => vec![days::find_day(day).ok_or(format!("There is no solution for day {}", day))?],
+        None => days::DAYS.iter().collect(),
+    };
+
+    let fuzz_dir = args.corpus.as_ref().map_or_else(|| days::workspace_dir().join("fuzz"), PathBuf::from);
+
+    let mut table = Table::new(&["day", "inputs", "parsed", "corpus", "crashes", "hangs"]);
+    let mut failed = false;
+    let mut hung_threads = Vec::new();
+
+    for day in selected {
+        let day_dir = fuzz_dir.join(format!("day{:02}", day.day));
+        let stats = panics::quietly(|| fuzz_day(day, &day_dir, &args))?;
+
+        failed |= stats.crashes + stats.hangs > 0;
+        hung_threads.extend(stats.hung_thread);
+        table.add_row(vec![
+            day.day.to_string(),
+            stats.inputs.to_string(),
+            stats.parsed.to_string(),
+            stats.corpus.to_string(),
+            stats.crashes.to_string(),
+            stats.hangs.to_string(),
+        ]);
+    }
+
+    table.print();
+
+    // There's no way to stop a thread, so the ones that hung carry on until
+    // the process exits, maybe slowing down the days fuzzed after them
+    let still_running = hung_threads.iter().filter(|thread| !thread.is_finished()).count();
+    if still_running > 0 {
+        eprintln!("\n{} hung input(s) were still running when fuzzing finished", still_running);
+    }
+
+    if failed {
+        println!("\nThe inputs that crashed or hung are in {}", fuzz_dir.join("dayNN/crashes").display());
+        return Ok(ExitCode::FAILURE)
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+struct Stats {
+    inputs: usize,
+    parsed: usize,
+    corpus: usize,
+    crashes: usize,
+    hangs: usize,
+    hung_thread: Option<JoinHandle<()>>,
+}
+
+fn fuzz_day(day: &Day, dir: &Path, args: &FuzzArgs) -> Result<Stats, String> {
+    let corpus_dir = dir.join("corpus");
+    let crash_dir = dir.join("crashes");
+    let in_flight = dir.join("in-flight");
+
+    let mut corpus = load_corpus(day.day, &corpus_dir)?;
+    let mut seen: HashSet::<Vec::<u8>> = corpus.iter().cloned().collect();
+    let mut seen_results = HashSet::<String>::new();
+
+    // Each day gets its own stream of inputs from the one seed
+    let mut rng = Rng::new(args.seed ^ ((day.day as u64) << 56));
+    let mut stats = Stats { inputs: 0, parsed: 0, corpus: corpus.len(), crashes: 0, hangs: 0, hung_thread: None };
+
+    for _ in 0..args.iterations {
+        let input = rng.pick(&corpus).clone();
+        let mutated = aoc::mutate(&mut rng, &input, &corpus, args.max_len);
+
+        if !seen.insert(mutated.clone()) {
+            continue
+        }
+
+        write(&in_flight, &mutated)?;
+        stats.inputs += 1;
+
+        match run_case(day.fuzz, mutated.clone(), args.timeout) {
+            Outcome::Parsed(results) => {
+                stats.parsed += 1;
+
+                if corpus.len() < MAX_CORPUS && aoc::is_new(&mut seen_results, &results) {
+                    write(&corpus_dir.join(format!("{:016x}", hash(&mutated))), &mutated)?;
+                    corpus.push(mutated);
+                }
+            },
+            Outcome::Rejected => {},
+            Outcome::Panicked(message) => {
+                stats.crashes += 1;
+                save_crash(&crash_dir, &mutated, &message)?;
+            },
+            Outcome::Hung(thread) => {
+                // The thread can't be stopped, so there's no point carrying on
+                // with this day while it eats a core
+                stats.hangs += 1;
+                stats.hung_thread = Some(thread);
+                save_crash(&crash_dir, &mutated, &format!("still running after {:?}", args.timeout))?;
+                eprintln!("Day {} hung on an input, its thread will keep running until the fuzzer exits", day.day);
+                break
+            },
+        }
+    }
+
+    remove_in_flight(&in_flight)?;
+    stats.corpus = corpus.len();
+
+    Ok(stats)
+}
+
+// There's nothing to remove if every input was one we'd already seen
+fn remove_in_flight(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        },
+        _ => Ok(()),
+    }
+}
+
+// Reads every input in the corpus directory, starting it off with the day's
+// test input the first time the day is fuzzed
+fn load_corpus(day: u8, dir: &Path) -> Result<Vec::<Vec::<u8>>, String> {
+    if !dir.exists() {
+        let test_input = days::day_dir(day).join("test_input.txt");
+        let seed = fs::read(&test_input).map_err(|e| format!("Failed to read {}: {}", test_input.display(), e))?;
+
+        write(&dir.join("test_input.txt"), &seed)?;
+    }
+
+    let mut entries: Vec::<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+
+    // Sorted so that the same seed gives the same inputs
+    entries.sort();
+
+    let corpus = entries
+        .iter()
+        .map(|path| fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e)))
+        .collect::<Result<Vec::<Vec::<u8>>, String>>()?;
+
+    if corpus.is_empty() {
+        return Err(format!("The corpus in {} is empty", dir.display()))
+    }
+
+    Ok(corpus)
+}
+
+fn run_case(target: FuzzFn, input: Vec::<u8>, timeout: Duration) -> Outcome {
+    let (sender, receiver) = mpsc::channel();
+
+    let spawned = thread::Builder::new()
+        .name(String::from("fuzz"))
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let outcome = match panics::catch(|| target(&input)) {
+                Ok(Some(results)) => Outcome::Parsed(results),
+                Ok(None) => Outcome::Rejected,
+                Err(message) => Outcome::Panicked(message),
+            };
+
+            // The receiver has gone if we were too slow
+            let _ = sender.send(outcome);
+        });
+
+    let thread = match spawned {
+        Ok(thread) => thread,
+        Err(e) => return Outcome::Panicked(format!("couldn't start a thread: {}", e)),
+    };
+
+    match receiver.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => Outcome::Hung(thread),
+        Err(RecvTimeoutError::Disconnected) => Outcome::Panicked(String::from("the thread died")),
+    }
+}
+
+// Writes the input that crashed, and what happened, as <hash>.txt and
+// <hash>.log so that the input can be fed straight back in with --input
+fn save_crash(dir: &Path, input: &[u8], message: &str) -> Result<(), String> {
+    let name = format!("{:016x}", hash(input));
+
+    write(&dir.join(format!("{}.txt", name)), input)?;
+    write(&dir.join(format!("{}.log", name)), format!("{}\n", message).as_bytes())
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// FNV-1a, which unlike the std hasher gives the same names on every machine
+fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_input_that_was_never_written_is_already_removed() {
+        let path = std::env::temp_dir().join("aoc-fuzz-test-never-written");
+        assert_eq!(remove_in_flight(&path), Ok(()));
+    }
+}
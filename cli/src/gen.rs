@@ -0,0 +1,36 @@
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use aoc::Answer;
+
+use crate::args::GenArgs;
+use crate::days;
+
+// Writes a generated input to stdout, exactly as it would appear in an input
+// file so that it can be piped straight into `aoc run --input -`, and the
+// answers to stderr
+pub fn gen(args: GenArgs) -> Result<ExitCode, String> {
+    let day = days::find_day(args.day)
+        .ok_or(format!("There is no solution for day {}", args.day))?;
+
+    let generated = (day.generate)(args.seed, &args.options);
+
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(generated.input.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|e| format!("Failed to write the input: {}", e))?;
+
+    eprintln!();
+    eprintln!("Part 1: {}", describe(&generated.part1));
+    eprintln!("Part 2: {}", describe(&generated.part2));
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn describe(answer: &Option<Answer>) -> String {
+    match answer {
+        Some(answer) => answer.to_string(),
+        None => String::from("unknown"),
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use aoc::{Answer, Detail, PartResult};
+
+// Just enough JSON to write out results, keeping the order of object keys so
+// the output is stable
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    String(String),
+    Array(Vec::<Json>),
+    Object(Vec::<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(fields: Vec::<(&str, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    pub fn string(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<&Answer> for Json {
+    fn from(answer: &Answer) -> Json {
+        match answer {
+            Answer::Number(n) => Json::Int(*n),
+            Answer::Text(s) => Json::string(s),
+        }
+    }
+}
+
+impl From<&Detail> for Json {
+    fn from(detail: &Detail) -> Json {
+        match detail {
+            Detail::Single(answer) => answer.into(),
+            Detail::List(answers) => Json::Array(answers.iter().map(Json::from).collect()),
+        }
+    }
+}
+
+// A part's answer (null if there isn't one) and its details
+impl From<&PartResult> for Json {
+    fn from(result: &PartResult) -> Json {
+        let details = result.details
+            .iter()
+            .map(|(name, detail)| (*name, detail.into()))
+            .collect();
+
+        Json::object(vec![
+            ("part", Json::Int(result.part.number() as i128)),
+            ("answer", result.answer.as_ref().map_or(Json::Null, Json::from)),
+            ("details", Json::object(details)),
+        ])
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Int(n) => write!(f, "{}", n),
+            // JSON has no infinities or NaN
+            Json::Float(n) if !n.is_finite() => write!(f, "null"),
+            Json::Float(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 { write!(f, ",")?; }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_nested_values() {
+        let json = Json::object(vec![
+            ("day", Json::Int(9)),
+            ("name", Json::string("a \"quoted\"\nline")),
+            ("nested", Json::object(vec![("release", Json::Bool(true))])),
+            ("values", Json::Array(vec![Json::Null, Json::Int(-1)])),
+        ]);
+
+        assert_eq!(
+            json.to_string(),
+            r#"{"day":9,"name":"a \"quoted\"\nline","nested":{"release":true},"values":[null,-1]}"#
+        );
+    }
+
+    #[test]
+    fn floats_without_a_json_value_are_null() {
+        let values = Json::Array(vec![Json::Float(225.6), Json::Float(2.0), Json::Float(f64::NAN)]);
+        assert_eq!(values.to_string(), "[225.6,2,null]");
+    }
+
+    #[test]
+    fn part_results_include_details() {
+        let result = PartResult {
+            part: aoc::Part::Two,
+            answer: Some(Answer::from(821560_usize)),
+            details: vec![("largest_basins", Detail::List(vec![Answer::from(95_u32), Answer::from(94_u32)]))],
+        };
+
+        assert_eq!(
+            Json::from(&result).to_string(),
+            r#"{"part":2,"answer":821560,"details":{"largest_basins":[95,94]}}"#
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+mod animate;
+mod args;
+mod batch;
+mod bench;
+mod days;
+mod fuzz;
+mod gen;
+mod json;
+mod panics;
+mod report;
+mod run_all;
+mod sweep;
+mod table;
+mod trajectory;
+mod verify;
+mod windows;
+
+use aoc::ImageFormat;
+
+use args::{Command, Format, RunArgs};
+use days::Day;
+use json::Json;
+
+fn run(args: RunArgs) -> Result<ExitCode, String> {
+    let day = days::find_day(args.day)
+        .ok_or(format!("There is no solution for day {}", args.day))?;
+
+    let (input, name) = days::read_input(args.day, &args.input)?;
+    let name = name.as_str();
+
+    if let Some(animation) = &args.animate {
+        let mut frames = (day.animate)(&input).map_err(|e| e.with_file(name).to_string())?.peekable();
+
+        if frames.peek().is_none() {
+            return Err(format!("Day {} doesn't have an animation", args.day))
+        }
+
+        animate::play(frames, animation.delay, animation.step_through)?;
+    }
+
+    let results = (day.solve)(&input, args.part)
+        .map_err(|e| e.with_file(name).to_string())?;
+
+    match args.format {
+        Format::Text => aoc::print_answers(&results),
+        Format::Json => {
+            let json = Json::object(vec![
+                ("day", Json::Int(args.day as i128)),
+                ("input", Json::string(name)),
+                ("parts", Json::Array(results.iter().map(Json::from).collect())),
+            ]);
+            println!("{}", json);
+        },
+    }
+
+    if let Some((path, format)) = &args.render {
+        render(day, &input, name, path, *format, args.step)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn render(
+    day: &Day, input: &str, name: &str, path: &str, format: ImageFormat, step: Option<u32>
+) -> Result<(), String> {
+    let image = (day.render)(input, step)
+        .map_err(|e| e.with_file(name).to_string())?
+        .ok_or(format!("Day {} doesn't have anything to render", day.day))?;
+
+    fs::write(path, image.encode(format))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn main() -> ExitCode {
+    let args: Vec::<String> = env::args().skip(1).collect();
+    let (verbosity, args) = args::take_verbosity(&args);
+    aoc::trace::set_verbosity(verbosity);
+
+    let command = match args::parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, args::USAGE);
+            return ExitCode::from(2)
+        }
+    };
+
+    let result = match command {
+        Command::Run(run_args) => run(run_args),
+        Command::RunAll(run_all_args) => run_all::run_all(run_all_args),
+        Command::RunInputs(run_inputs_args) => batch::run_inputs(run_inputs_args),
+        Command::RunWindows(run_windows_args) => windows::run_windows(run_windows_args),
+        Command::Sweep(sweep_args) => sweep::sweep(sweep_args),
+        Command::Report(report_args) => report::report(report_args),
+        Command::Trajectory(trajectory_args) => trajectory::trajectory(trajectory_args),
+        Command::Verify(verify_args) => verify::verify(verify_args),
+        Command::Bench(bench_args) => bench::bench(bench_args),
+        Command::Gen(gen_args) => gen::gen(gen_args),
+        Command::Fuzz(fuzz_args) => fuzz::fuzz(fuzz_args),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        ExitCode::FAILURE
+    })
+}
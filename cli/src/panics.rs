@@ -0,0 +1,101 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    // Where the quiet panic hook leaves the message for `catch` to pick up
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+    // Whether panics on this thread should be kept rather than printed
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+// The hook is installed once and left in place, since threads swapping hooks
+// in and out at the same time can put back each other's hook. Panics on
+// threads that haven't asked to be quiet still go to the hook that was there
+// before.
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if QUIET.get() {
+                PANIC_MESSAGE.with(|message| *message.borrow_mut() = Some(info.to_string()));
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+// Runs f keeping the message of each panic on this thread for `catch` rather
+// than printing it, so that a day which panics doesn't spray its message and
+// backtrace over the middle of our output
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    install_hook();
+
+    let was_quiet = QUIET.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.set(was_quiet);
+
+    result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+// Runs f, giving where and why it panicked (on one line) if it does. f is run
+// quietly, as it's often on a thread of its own that was spawned inside of
+// `quietly`.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    quietly(|| panic::catch_unwind(AssertUnwindSafe(f))).map_err(|payload| {
+        PANIC_MESSAGE
+            .with(|message| message.take())
+            .unwrap_or_else(|| payload_message(payload.as_ref()))
+            .replace('\n', " ")
+    })
+}
+
+// Without the quiet hook all we have is the value passed to panic!, which is
+// nearly always a string
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => String::from("panicked"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panics_are_caught_with_their_message() {
+        let caught = quietly(|| catch(|| -> u32 { panic!("no answer for {}", 7) }));
+        let message = caught.unwrap_err();
+
+        assert!(message.starts_with("panicked at cli/src/panics.rs:"), "{}", message);
+        assert!(message.ends_with(" no answer for 7"), "{}", message);
+    }
+
+    #[test]
+    fn threads_catching_at_once_keep_their_own_messages() {
+        let messages: Vec::<String> = std::thread::scope(|scope| {
+            let handles: Vec::<_> = (0..16)
+                .map(|i| scope.spawn(move || quietly(|| catch(|| -> u32 { panic!("thread {} panicked", i) }))))
+                .collect();
+
+            handles.into_iter().map(|handle| handle.join().unwrap().unwrap_err()).collect()
+        });
+
+        for (i, message) in messages.iter().enumerate() {
+            assert!(message.starts_with("panicked at cli/src/panics.rs:"), "{}", message);
+            assert!(message.ends_with(&format!(" thread {} panicked", i)), "{}", message);
+        }
+        assert!(!QUIET.get());
+    }
+
+    #[test]
+    fn values_are_passed_through() {
+        assert_eq!(quietly(|| catch(|| 5)), Ok(5));
+    }
+}
@@ -0,0 +1,136 @@
+use std::fs;
+use std::process::ExitCode;
+
+use aoc::Solution;
+use ex01::{Day01, Report, Run, Step};
+
+use crate::args::{Format, ReportArgs};
+use crate::days;
+use crate::json::Json;
+use crate::table::Table;
+
+// Describes the shape of day 1's sonar sweep, with the windowed means written
+// out as CSV if asked for
+pub fn report(args: ReportArgs) -> Result<ExitCode, String> {
+    let (input, name) = days::read_input(1, &args.input)?;
+    let depths = Day01::parse(&input).map_err(|e| e.with_file(&name).to_string())?;
+
+    match args.csv.as_deref() {
+        // The CSV takes the place of the report on stdout
+        Some("-") => {
+            print!("{}", ex01::means_csv(&depths, args.window));
+            return Ok(ExitCode::SUCCESS)
+        },
+        Some(path) => {
+            fs::write(path, ex01::means_csv(&depths, args.window))
+                .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        },
+        None => {},
+    }
+
+    let report = ex01::report(&depths);
+
+    match args.format {
+        Format::Text => print_report(&report),
+        Format::Json => println!("{}", to_json(&report, &name)),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_report(report: &Report) {
+    let mut table = Table::new(&["statistic", "value"]);
+
+    for (name, value) in describe(report) {
+        table.add_row(vec![name.to_string(), value]);
+    }
+
+    table.print();
+}
+
+// e.g. "4 depths from 0"
+fn describe_run(run: Option<Run>) -> String {
+    run.map_or_else(|| String::from("-"), |run| format!("{} depths from {}", run.len, run.start))
+}
+
+// e.g. "+33 at 6"
+fn describe_step(step: Option<Step>) -> String {
+    step.map_or_else(|| String::from("-"), |step| format!("{:+} at {}", step.change, step.index))
+}
+
+fn describe_value(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| String::from("-"), |value| value.to_string())
+}
+
+// A row for each statistic, with - for the ones the sweep is too short for
+fn describe(report: &Report) -> Vec::<(&'static str, String)> {
+    let longest_plateau = report.plateaus.iter().rev().max_by_key(|run| run.len).copied();
+    let plateaus = match longest_plateau {
+        Some(longest) => format!("{}, the longest {}", report.plateaus.len(), describe_run(Some(longest))),
+        None => String::from("0"),
+    };
+
+    vec![
+        ("depths", report.depths.to_string()),
+        ("longest increase", describe_run(report.longest_increase)),
+        ("longest decrease", describe_run(report.longest_decrease)),
+        ("plateaus", plateaus),
+        ("largest jump", describe_step(report.largest_jump)),
+        ("largest drop", describe_step(report.largest_drop)),
+        ("min", describe_value(report.min)),
+        ("max", describe_value(report.max)),
+        ("mean", describe_value(report.mean.map(|mean| format!("{:.2}", mean)))),
+    ]
+}
+
+fn run_json(run: Option<Run>) -> Json {
+    run.map_or(Json::Null, |run| {
+        Json::object(vec![("start", Json::Int(run.start as i128)), ("len", Json::Int(run.len as i128))])
+    })
+}
+
+fn step_json(step: Option<Step>) -> Json {
+    step.map_or(Json::Null, |step| {
+        Json::object(vec![("index", Json::Int(step.index as i128)), ("change", Json::Int(step.change as i128))])
+    })
+}
+
+fn to_json(report: &Report, name: &str) -> Json {
+    Json::object(vec![
+        ("day", Json::Int(1)),
+        ("input", Json::string(name)),
+        ("depths", Json::Int(report.depths as i128)),
+        ("longest_increase", run_json(report.longest_increase)),
+        ("longest_decrease", run_json(report.longest_decrease)),
+        ("plateaus", Json::Array(report.plateaus.iter().map(|run| run_json(Some(*run))).collect())),
+        ("largest_jump", step_json(report.largest_jump)),
+        ("largest_drop", step_json(report.largest_drop)),
+        ("min", report.min.map_or(Json::Null, |min| Json::Int(min as i128))),
+        ("max", report.max.map_or(Json::Null, |max| Json::Int(max as i128))),
+        ("mean", report.mean.map_or(Json::Null, Json::Float)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_are_described() {
+        let rows = describe(&ex01::report(&[199, 200, 208, 208, 200, 207]));
+
+        assert_eq!(rows[1], ("longest increase", String::from("3 depths from 0")));
+        assert_eq!(rows[3], ("plateaus", String::from("1, the longest 2 depths from 2")));
+        assert_eq!(rows[5], ("largest drop", String::from("-8 at 4")));
+        assert_eq!(rows[8], ("mean", String::from("203.67")));
+    }
+
+    #[test]
+    fn short_sweeps_leave_gaps() {
+        let rows = describe(&ex01::report(&[]));
+
+        assert_eq!(rows[1].1, "-");
+        assert_eq!(rows[3].1, "0");
+        assert!(to_json(&ex01::report(&[]), "x").to_string().contains("\"mean\":null"));
+    }
+}
@@ -0,0 +1,164 @@
+use std::process::ExitCode;
+use std::thread;
+
+use aoc::{Part, PartResult, Timed};
+
+use crate::args::{Format, RunAllArgs};
+use crate::bench::format_duration;
+use crate::days::{self, Day};
+use crate::json::Json;
+use crate::panics;
+use crate::table::Table;
+
+// How one day got on. Each day runs on its own thread, and anything that goes
+// wrong (a missing input, a parse error, even a panic) only ends up in that
+// day's row.
+enum Outcome {
+    Solved(Timed),
+    Failed(String),
+    Panicked(String),
+}
+
+struct DayRun {
+    day: u8,
+    input: String,
+    outcome: Outcome,
+}
+
+// Runs every day at once, printing a table with a row for each day once they
+// have all finished
+pub fn run_all(args: RunAllArgs) -> Result<ExitCode, String> {
+    let runs: Vec::<DayRun> = panics::quietly(|| {
+        thread::scope(|scope| {
+            let handles: Vec::<_> = days::DAYS
+                .iter()
+                .map(|day| scope.spawn(|| run_day(day, &args)))
+                .collect();
+
+            // run_day catches its own panics, so joining can't fail
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        })
+    });
+
+    match args.format {
+        Format::Text => print_table(&runs),
+        Format::Json => println!("{}", Json::Array(runs.iter().map(Json::from).collect())),
+    }
+
+    if runs.iter().all(|run| matches!(run.outcome, Outcome::Solved(_))) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn run_day(day: &Day, args: &RunAllArgs) -> DayRun {
+    let filepath = days::input_path(day.day, &args.input);
+    let input = filepath.to_string_lossy().to_string();
+
+    let outcome = match aoc::get_file_input(&input) {
+        Err(e) => Outcome::Failed(format!("Failed to read {}: {}", input, e)),
+        Ok(text) => match panics::catch(|| (day.solve_timed)(&text, args.part)) {
+            Ok(Ok(timed)) => Outcome::Solved(timed),
+            Ok(Err(e)) => Outcome::Failed(e.with_file(&input).to_string()),
+            Err(message) => Outcome::Panicked(message),
+        },
+    };
+
+    DayRun { day: day.day, input, outcome }
+}
+
+fn print_table(runs: &[DayRun]) {
+    let mut table = Table::new(&["day", "part 1", "part 2", "parse", "solve", "status"]);
+
+    for run in runs {
+        let row = match &run.outcome {
+            Outcome::Solved(timed) => vec![
+                describe(&timed.results, Part::One),
+                describe(&timed.results, Part::Two),
+                format_duration(timed.parse),
+                format_duration(timed.solve),
+                String::from("ok"),
+            ],
+            Outcome::Failed(error) | Outcome::Panicked(error) => vec![
+                String::from("-"),
+                String::from("-"),
+                String::from("-"),
+                String::from("-"),
+                error.clone(),
+            ],
+        };
+
+        table.add_row([vec![run.day.to_string()], row].concat());
+    }
+
+    table.print();
+}
+
+// The answer to one part, which is blank when it wasn't run
+pub fn describe(results: &[PartResult], part: Part) -> String {
+    match results.iter().find(|result| result.part == part) {
+        Some(PartResult { answer: Some(answer), .. }) => answer.to_string(),
+        Some(PartResult { answer: None, .. }) => String::from("no answer"),
+        None => String::new(),
+    }
+}
+
+impl From<&DayRun> for Json {
+    fn from(run: &DayRun) -> Json {
+        let mut fields = vec![
+            ("day", Json::Int(run.day as i128)),
+            ("input", Json::string(&run.input)),
+        ];
+
+        match &run.outcome {
+            Outcome::Solved(timed) => {
+                fields.push(("status", Json::string("ok")));
+                fields.push(("parse_ns", Json::Int(timed.parse.as_nanos() as i128)));
+                fields.push(("solve_ns", Json::Int(timed.solve.as_nanos() as i128)));
+                fields.push(("parts", Json::Array(timed.results.iter().map(Json::from).collect())));
+            },
+            Outcome::Failed(error) => {
+                fields.push(("status", Json::string("error")));
+                fields.push(("error", Json::string(error)));
+            },
+            Outcome::Panicked(message) => {
+                fields.push(("status", Json::string("panicked")));
+                fields.push(("error", Json::string(message)));
+            },
+        }
+
+        Json::object(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::ParseError;
+
+    use crate::args::InputSource;
+
+    fn panicking_solve(_input: &str, _part: Option<Part>) -> Result<Timed, ParseError> {
+        panic!("bad binary string")
+    }
+
+    #[test]
+    fn panics_are_reported_in_their_days_row() {
+        let day = Day { solve_timed: panicking_solve, ..days::DAYS[2] };
+        let args = RunAllArgs { part: None, input: InputSource::Test, format: Format::Text };
+
+        let run = panics::quietly(|| run_day(&day, &args));
+        assert!(matches!(&run.outcome, Outcome::Panicked(message) if message.ends_with("bad binary string")));
+    }
+
+    #[test]
+    fn test_inputs_are_solved() {
+        let args = RunAllArgs { part: Some(Part::One), input: InputSource::Test, format: Format::Text };
+        let run = run_day(&days::DAYS[0], &args);
+
+        let Outcome::Solved(timed) = run.outcome else { panic!("day 1 wasn't solved") };
+        assert_eq!(describe(&timed.results, Part::One), "7");
+        assert_eq!(describe(&timed.results, Part::Two), "");
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+
+use aoc::{parser, ParseError};
+use ex01::{Running, SweepExt};
+
+use crate::args::{Format, SweepArgs};
+use crate::days;
+use crate::json::Json;
+use crate::table::Table;
+
+// Counts day 1's windows as the depths are read, so that a sweep from stdin
+// can go on for as long as it likes. The running counts are printed every so
+// often, and a summary once the depths run out.
+pub fn sweep(args: SweepArgs) -> Result<ExitCode, String> {
+    let filepath = days::input_path(1, &args.input);
+    let filepath = filepath.to_string_lossy();
+    let name = if filepath == "-" { "<stdin>" } else { &filepath };
+
+    let reader: Box<dyn BufRead> = if filepath == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(&*filepath).map_err(|e| format!("Failed to read {}: {}", filepath, e))?;
+        Box::new(BufReader::new(file))
+    };
+
+    // The sweep stops at the first depth that can't be read, leaving the
+    // error here for afterwards
+    let mut error = None;
+    let depths = aoc::input::stream_lines(reader).map_while(|line| {
+        match read_depth(line, name) {
+            Ok(depth) => Some(depth),
+            Err(e) => {
+                error = Some(e);
+                None
+            },
+        }
+    });
+
+    let mut last = None;
+    for running in depths.sweep(&args.windows, args.compare) {
+        if running.depths % args.every == 0 {
+            print_running(&running, &args);
+        }
+
+        last = Some(running);
+    }
+
+    if let Some(e) = error {
+        return Err(e)
+    }
+
+    let last = last.unwrap_or(Running { depths: 0, depth: 0, counts: vec![0; args.windows.len()] });
+    print_summary(&last, &args);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read_depth(line: io::Result<(usize, String)>, name: &str) -> Result<u32, String> {
+    let (i, line) = line.map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    parser::parse_line(i, &line, parser::integer("a depth"))
+        .map_err(|e: ParseError| e.with_file(name).to_string())
+}
+
+fn print_running(running: &Running, args: &SweepArgs) {
+    match args.format {
+        Format::Text => println!("{}", describe(running, args)),
+        Format::Json => println!("{}", to_json(running, args, false)),
+    }
+}
+
+fn print_summary(last: &Running, args: &SweepArgs) {
+    match args.format {
+        Format::Text => {
+            println!("{} depths", last.depths);
+
+            let mut table = Table::new(&["window", args.compare.name()]);
+            for (window, count) in args.windows.iter().zip(&last.counts) {
+                table.add_row(vec![window.to_string(), count.to_string()]);
+            }
+            table.print();
+        },
+        Format::Json => println!("{}", to_json(last, args, true)),
+    }
+}
+
+// e.g. "after 1000 depths: 512 with a window of 1, 498 with a window of 3"
+fn describe(running: &Running, args: &SweepArgs) -> String {
+    let counts: Vec::<String> = args
+        .windows
+        .iter()
+        .zip(&running.counts)
+        .map(|(window, count)| format!("{} with a window of {}", count, window))
+        .collect();
+
+    let depths = if running.depths == 1 { "depth" } else { "depths" };
+    format!("after {} {}: {}", running.depths, depths, counts.join(", "))
+}
+
+// One object per line, with the summary marked as the last of them
+fn to_json(running: &Running, args: &SweepArgs, summary: bool) -> Json {
+    let windows = args
+        .windows
+        .iter()
+        .zip(&running.counts)
+        .map(|(window, count)| {
+            Json::object(vec![("window", Json::Int(*window as i128)), ("count", Json::Int(*count as i128))])
+        })
+        .collect();
+
+    Json::object(vec![
+        ("depths", Json::Int(running.depths as i128)),
+        ("compare", Json::string(args.compare.name())),
+        ("windows", Json::Array(windows)),
+        ("summary", Json::Bool(summary)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::InputSource;
+    use ex01::Compare;
+
+    fn args() -> SweepArgs {
+        SweepArgs {
+            input: InputSource::Test,
+            windows: vec![1, 3],
+            compare: Compare::Increase,
+            every: 1000,
+            format: Format::Text,
+        }
+    }
+
+    #[test]
+    fn running_counts_are_described() {
+        let running = Running { depths: 1000, depth: 5, counts: vec![512, 498] };
+
+        assert_eq!(describe(&running, &args()), "after 1000 depths: 512 with a window of 1, 498 with a window of 3");
+        assert_eq!(
+            to_json(&running, &args(), true).to_string(),
+            "{\"depths\":1000,\"compare\":\"increase\",\"windows\":[{\"window\":1,\"count\":512},\
+             {\"window\":3,\"count\":498}],\"summary\":true}"
+        );
+    }
+
+    #[test]
+    fn bad_depths_point_at_the_line() {
+        let error = read_depth(Ok((4, String::from("2O0"))), "<stdin>").unwrap_err();
+        assert_eq!(error, "<stdin>:5:1: found \"2O0\", expected a depth");
+    }
+}
@@ -0,0 +1,43 @@
+// A plain text table, with each column padded out to its widest cell
+pub struct Table {
+    headers: Vec::<String>,
+    rows: Vec::<Vec::<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec::<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn print(&self) {
+        let mut widths: Vec::<usize> = self.headers.iter().map(|h| h.len()).collect();
+
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        print_row(&self.headers, &widths);
+        for row in &self.rows {
+            print_row(row, &widths);
+        }
+    }
+}
+
+fn print_row(row: &[String], widths: &[usize]) {
+    let cells: Vec::<String> = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+
+    println!("{}", cells.join("  ").trim_end());
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::process::ExitCode;
+
+use ex02::{Model, Stop, Trajectory};
+
+use crate::args::{Format, TrajectoryArgs};
+use crate::days;
+use crate::json::Json;
+use crate::table::Table;
+
+// Follows day 2's submarine move by move. The input is read as a pilot
+// script, which any puzzle input already is.
+pub fn trajectory(args: TrajectoryArgs) -> Result<ExitCode, String> {
+    let (input, name) = days::read_input(2, &args.input)?;
+    let script = ex02::parse_script(&input).map_err(|e| e.with_file(&name).to_string())?;
+    let trajectory = Trajectory::new(args.model, script.moves());
+
+    // Said on stderr too, as the exports don't have anywhere to say it
+    if trajectory.stopped == Some(Stop::TooManyMoves) {
+        eprintln!("{} makes more than {} moves, only the first of them are followed", name, ex02::MAX_MOVES);
+    }
+
+    // Either export can take the place of the summary on stdout
+    let printed_csv = export(args.csv.as_deref(), || trajectory.to_csv())?;
+    let printed_svg = export(args.svg.as_deref(), || trajectory.to_svg())?;
+
+    if printed_csv || printed_svg {
+        return Ok(ExitCode::SUCCESS)
+    }
+
+    match args.format {
+        Format::Text => {
+            let mut table = Table::new(&["model", args.model.name()]);
+            for (name, value) in describe(&trajectory, args.deeper_than) {
+                table.add_row(vec![name.to_string(), value]);
+            }
+            table.print();
+        },
+        Format::Json => println!("{}", to_json(&trajectory, args.deeper_than, &name)),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// Writes an export to path, or prints it if path is -, giving whether it was
+// printed. It's only made if it's wanted, as a long trajectory is a big file.
+fn export(path: Option<&str>, contents: impl FnOnce() -> String) -> Result<bool, String> {
+    match path {
+        Some("-") => print!("{}", contents()),
+        Some(path) => fs::write(path, contents()).map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        None => {},
+    }
+
+    Ok(path == Some("-"))
+}
+
+// e.g. "after move 3", or "never"
+fn describe_move(moves: Option<usize>) -> String {
+    moves.map_or_else(|| String::from("never"), |moves| format!("after move {}", moves))
+}
+
+fn describe(trajectory: &Trajectory, deeper_than: Option<i64>) -> Vec::<(String, String)> {
+    let last = trajectory.last();
+    let mut rows = vec![
+        (String::from("moves"), trajectory.moves.len().to_string()),
+        (String::from("position"), trajectory.model.describe(&last)),
+        (String::from("max depth"), trajectory.max_depth().to_string()),
+    ];
+
+    if let Some(depth) = deeper_than {
+        rows.push((format!("deeper than {}", depth), describe_move(trajectory.first_deeper_than(depth))));
+    }
+
+    rows.push((String::from("surfaces"), describe_move(trajectory.first_surfacing())));
+
+    let stopped = match trajectory.stopped {
+        Some(Stop::Overflow) => Some(format!("too deep to work out at move {}", trajectory.moves.len() + 1)),
+        Some(Stop::TooManyMoves) => Some(format!("after the first {} moves", trajectory.moves.len())),
+        None => None,
+    };
+    if let Some(stopped) = stopped {
+        rows.push((String::from("stopped"), stopped));
+    }
+
+    rows
+}
+
+fn moves_json(moves: Option<usize>) -> Json {
+    moves.map_or(Json::Null, |moves| Json::Int(moves as i128))
+}
+
+fn to_json(trajectory: &Trajectory, deeper_than: Option<i64>, name: &str) -> Json {
+    let last = trajectory.last();
+    let mut fields = vec![
+        ("day", Json::Int(2)),
+        ("input", Json::string(name)),
+        ("model", Json::string(trajectory.model.name())),
+        ("moves", Json::Int(trajectory.moves.len() as i128)),
+        ("horizontal", Json::Int(last.horizontal as i128)),
+        ("depth", Json::Int(last.depth as i128)),
+    ];
+
+    if trajectory.model == Model::Aim {
+        fields.push(("aim", Json::Int(last.aim as i128)));
+    }
+
+    fields.push(("max_depth", Json::Int(trajectory.max_depth() as i128)));
+
+    if let Some(depth) = deeper_than {
+        let deeper = Json::object(vec![
+            ("depth", Json::Int(depth as i128)),
+            ("move", moves_json(trajectory.first_deeper_than(depth))),
+        ]);
+        fields.push(("deeper_than", deeper));
+    }
+
+    fields.push(("surfaces", moves_json(trajectory.first_surfacing())));
+    let stopped = match trajectory.stopped {
+        Some(Stop::Overflow) => Json::string("overflow"),
+        Some(Stop::TooManyMoves) => Json::string("too many moves"),
+        None => Json::Null,
+    };
+    fields.push(("stopped", stopped));
+
+    Json::object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trajectory(model: Model, source: &str) -> Trajectory {
+        Trajectory::new(model, ex02::parse_script(source).unwrap().moves())
+    }
+
+    #[test]
+    fn trajectories_are_described() {
+        let rows = describe(&trajectory(Model::Aim, "down 5\nforward 8\nup 6\nforward 1"), Some(39));
+        let rows: Vec::<String> = rows.into_iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+
+        assert_eq!(rows, vec![
+            "moves: 4",
+            "position: at 9, depth 39, aim -1",
+            "max depth: 40",
+            "deeper than 39: after move 2",
+            "surfaces: never",
+        ]);
+    }
+
+    #[test]
+    fn surfacing_and_overflow_show_up_in_json() {
+        let json = to_json(&trajectory(Model::Plain, "up 1"), None, "x").to_string();
+        assert!(json.contains("\"surfaces\":1,\"stopped\":null"));
+
+        let course = ["down 2147483647", "forward 2147483647"].repeat(3).join("\n");
+        let json = to_json(&trajectory(Model::Aim, &course), None, "x").to_string();
+        assert!(json.contains("\"stopped\":\"overflow\""));
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use aoc::{Answer, ParseError, PartResult, RegisteredAnswer};
+
+use crate::args::VerifyArgs;
+use crate::days;
+use crate::panics;
+use crate::table::Table;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Mismatch,
+    Fail,
+    Panicked,
+}
+
+// Runs every registered input through its day and checks the answers match
+// the ones in the answers file
+pub fn verify(args: VerifyArgs) -> Result<ExitCode, String> {
+    let answers_path = args.answers.map_or_else(|| days::workspace_dir().join("answers.toml"), |p| p.into());
+    let answers_file = answers_path.to_string_lossy();
+
+    let text = aoc::get_file_input(&answers_file)
+        .map_err(|e| format!("Failed to read {}: {}", answers_file, e))?;
+
+    let registered: Vec::<RegisteredAnswer> = aoc::parse_answers(&text)
+        .map_err(|e| e.with_file(&answers_file).to_string())?
+        .into_iter()
+        .filter(|answer| args.day.is_none_or(|day| answer.day == day))
+        .collect();
+
+    let answers_dir = answers_path.parent().unwrap_or(Path::new("."));
+
+    let mut table = Table::new(&["day", "part", "input", "expected", "actual", "status"]);
+    let (mut passed, mut mismatched, mut failed, mut panicked) = (0, 0, 0, 0);
+
+    for answer in &registered {
+        let (actual, status) = panics::quietly(|| check_answer(answer, answers_dir));
+
+        let status = match status {
+            Status::Pass => { passed += 1; "pass" },
+            Status::Mismatch => { mismatched += 1; "MISMATCH" },
+            Status::Fail => { failed += 1; "FAIL" },
+            Status::Panicked => { panicked += 1; "PANICKED" },
+        };
+
+        table.add_row(vec![
+            answer.day.to_string(),
+            answer.part.to_string(),
+            answer.input.clone(),
+            answer.expected.to_string(),
+            actual,
+            status.to_string(),
+        ]);
+    }
+
+    table.print();
+    println!("\n{} passed, {} mismatched, {} failed, {} panicked", passed, mismatched, failed, panicked);
+
+    if mismatched + failed + panicked > 0 {
+        return Ok(ExitCode::FAILURE)
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// Solves the registered part of the input, returning what was actually found
+// (or why nothing was found) along with the status
+fn check_answer(answer: &RegisteredAnswer, answers_dir: &Path) -> (String, Status) {
+    let Some(day) = days::find_day(answer.day) else {
+        return (String::from("no solution for this day"), Status::Fail)
+    };
+
+    let filepath = answers_dir.join(&answer.input);
+    let input = match aoc::get_file_input(&filepath.to_string_lossy()) {
+        Ok(input) => input,
+        Err(e) => return (e.to_string(), Status::Fail),
+    };
+
+    // A day that panics only spoils its own row
+    judge(answer, panics::catch(|| (day.solve)(&input, Some(answer.part))))
+}
+
+// What the day found, or the error or panic message if it didn't get that far
+fn judge(
+    answer: &RegisteredAnswer, solved: Result<Result<Vec::<PartResult>, ParseError>, String>
+) -> (String, Status) {
+    let actual: Option<Answer> = match solved {
+        Ok(Ok(results)) => results.into_iter().next().and_then(|result| result.answer),
+        Ok(Err(e)) => return (e.to_string(), Status::Fail),
+        Err(message) => return (message, Status::Panicked),
+    };
+
+    match actual {
+        Some(actual) if actual == answer.expected => (actual.to_string(), Status::Pass),
+        Some(actual) => (actual.to_string(), Status::Mismatch),
+        None => (String::from("no answer"), Status::Fail),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Part;
+
+    #[test]
+    fn a_panicking_day_is_its_own_row() {
+        let answer = RegisteredAnswer { day: 3, part: Part::One, input: String::from("x"), expected: 7.into() };
+        let solve = || -> Result<Vec::<PartResult>, ParseError> { panic!("boom") };
+        let solved = panics::quietly(|| panics::catch(solve));
+
+        let (actual, status) = judge(&answer, solved);
+        assert!(actual.ends_with("boom"));
+        assert_eq!(status, Status::Panicked);
+    }
+}
@@ -0,0 +1,114 @@
+use std::process::ExitCode;
+
+use aoc::Solution;
+use ex01::{Compare, Day01, Filter};
+
+use crate::args::{Format, RunWindowsArgs};
+use crate::days;
+use crate::json::Json;
+use crate::table::Table;
+
+// Counts the windows of depths in day 1's sonar sweep that compare a
+// particular way to the window before them, rather than solving the parts.
+// With filters, the windows are counted again after each of them so the raw
+// and smoothed counts can be compared.
+pub fn run_windows(args: RunWindowsArgs) -> Result<ExitCode, String> {
+    let (input, name) = days::read_input(1, &args.input)?;
+    let depths = Day01::parse(&input).map_err(|e| e.with_file(&name).to_string())?;
+    let count = ex01::count_windows(&depths, args.window, args.compare);
+    let filtered = filtered_counts(&depths, &args);
+
+    match args.format {
+        Format::Text => {
+            println!("{}", describe(args.window, args.compare, count));
+
+            if !filtered.is_empty() {
+                let mut table = Table::new(&["filters", "count"]);
+                for (filters, count) in &filtered {
+                    table.add_row(vec![describe_filters(filters), count.to_string()]);
+                }
+                table.print();
+            }
+        },
+        Format::Json => {
+            let filtered = filtered
+                .iter()
+                .map(|(filters, count)| {
+                    let filters = filters.iter().map(|filter| Json::string(&filter.to_string())).collect();
+                    Json::object(vec![("filters", Json::Array(filters)), ("count", Json::Int(*count as i128))])
+                })
+                .collect();
+
+            let json = Json::object(vec![
+                ("day", Json::Int(1)),
+                ("input", Json::string(&name)),
+                ("window", Json::Int(args.window as i128)),
+                ("compare", Json::string(args.compare.name())),
+                ("count", Json::Int(count as i128)),
+                ("filtered", Json::Array(filtered)),
+            ]);
+            println!("{}", json);
+        },
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+// The count after the first filter, then the first two, and so on
+fn filtered_counts<'a>(depths: &[u32], args: &'a RunWindowsArgs) -> Vec::<(&'a [Filter], usize)> {
+    (1..=args.filters.len())
+        .map(|n| {
+            let filters = &args.filters[..n];
+            let filtered = ex01::filter(depths, filters);
+            (filters, ex01::count_windows(&filtered, args.window, args.compare))
+        })
+        .collect()
+}
+
+// e.g. "median:3 then hysteresis:2"
+fn describe_filters(filters: &[Filter]) -> String {
+    filters.iter().map(|filter| filter.to_string()).collect::<Vec::<_>>().join(" then ")
+}
+
+fn describe(window: usize, compare: Compare, count: usize) -> String {
+    let depths = if window == 1 { "depth" } else { "depths" };
+    let comparison = match compare {
+        Compare::Increase => "deeper than",
+        Compare::Decrease => "shallower than",
+        Compare::Equal => "as deep as",
+    };
+
+    format!("{} windows of {} {} are {} the window before", count, window, depths, comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::InputSource;
+
+    #[test]
+    fn counts_are_described() {
+        assert_eq!(describe(1, Compare::Increase, 7), "7 windows of 1 depth are deeper than the window before");
+        assert_eq!(describe(5, Compare::Equal, 0), "0 windows of 5 depths are as deep as the window before");
+    }
+
+    #[test]
+    fn filters_are_counted_one_after_another() {
+        let args = RunWindowsArgs {
+            input: InputSource::Test,
+            format: Format::Text,
+            window: 1,
+            compare: Compare::Increase,
+            filters: vec![Filter::Median(3), Filter::Hysteresis(5)],
+        };
+        let depths = [200, 201, 200, 230, 201, 202, 209, 210];
+
+        let counts: Vec::<(String, usize)> = filtered_counts(&depths, &args)
+            .into_iter()
+            .map(|(filters, count)| (describe_filters(filters), count))
+            .collect();
+
+        let expected = vec![(String::from("median:3"), 3), (String::from("median:3 then hysteresis:5"), 1)];
+        assert_eq!(counts, expected);
+    }
+}
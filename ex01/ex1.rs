@@ -1,55 +1,189 @@
-use std::io::Error;
-use std::fs;
+use aoc::{parser, Answer, ParseError, Part, Solution, Variant};
 
-fn number_of_increases(input_vec: &[u32]) -> u32 {
-    let mut number_of_increases = 0;
+mod filter;
+mod generate;
+mod report;
+mod sweep;
 
-    for i in 1..input_vec.len() {
-        if input_vec[i] > input_vec[i - 1] {
-            number_of_increases += 1;
+pub use filter::{filter, Filter};
+pub use report::{means_csv, report, windowed_means, Report, Run, Step};
+pub use sweep::{Running, Sweep, SweepExt};
+
+// How a window of depths compares to the window one depth before it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compare {
+    Increase,
+    Decrease,
+    Equal,
+}
+
+impl Compare {
+    pub fn from_name(name: &str) -> Option<Compare> {
+        match name {
+            "increase" => Some(Compare::Increase),
+            "decrease" => Some(Compare::Decrease),
+            "equal" => Some(Compare::Equal),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Compare::Increase => "increase",
+            Compare::Decrease => "decrease",
+            Compare::Equal => "equal",
         }
     }
 
-    number_of_increases
+    fn matches<T: Ord>(self, earlier: T, later: T) -> bool {
+        match self {
+            Compare::Increase => later > earlier,
+            Compare::Decrease => later < earlier,
+            Compare::Equal => later == earlier,
+        }
+    }
 }
 
-fn sliding_window(input_vec: &[u32]) -> u32 {
-    // the final sliding window is centered on the penultimate element
-    let max_index = input_vec.len() - 1;
+// Counts the windows of `window` depths that compare to the window before
+// them, e.g. part 1 is windows of one depth that increase and part 2 is
+// windows of three.
+//
+// Next to each other the windows share all but their first and last depths:
+//   previous = v[i - k] + v[i - k + 1] + ... + v[i - 1]
+//   current  =            v[i - k + 1] + ... + v[i - 1] + v[i]
+// so comparing the sums is the same as comparing v[i] against v[i - k], and
+// we never have to add anything up.
+//
+// With fewer than window + 1 depths there aren't two windows to compare, so
+// the count is 0, as it is for a window of 0 depths.
+pub fn count_windows(depths: &[u32], window: usize, compare: Compare) -> usize {
+    if window == 0 {
+        return 0
+    }
 
-    // the second sliding window (i.e. the first that we compare) 
-    // is centered on the 3rd element
-    let min_index = 2;
+    (window..depths.len())
+        .filter(|i| {
+            let matches = compare.matches(depths[i - window], depths[*i]);
+            if matches {
+                aoc::trace::event(|| format!("{} at the window ending at depth {}", compare.name(), i + 1));
+            }
+            matches
+        })
+        .count()
+}
 
-    let mut number_of_increases = 0;
+// The straightforward way, actually summing each window, to check the
+// shortcut above with
+fn summing_windows(depths: &[u32], window: usize, compare: Compare) -> usize {
+    if window == 0 {
+        return 0
+    }
 
-    for i in min_index..max_index {
-        // this works because:
-        // previous_height = input_vec[i - 2] + input_vec[i - 1] + input_vec[i];
-        // current_height = input_vec[i - 1] + input_vec[i] + input_vec[i + 1];
-        
-        if input_vec[i + 1] > input_vec[i - 2] {
-            number_of_increases += 1;
-        }
+    let sums: Vec::<u64> = depths.windows(window).map(|w| w.iter().map(|d| *d as u64).sum()).collect();
+    sums.windows(2).filter(|pair| compare.matches(pair[0], pair[1])).count()
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec::<u32>;
+
+    fn parse(input: &str) -> Result<Vec::<u32>, ParseError> {
+        parser::parse_lines(input, parser::integer("a depth"))
+    }
+
+    fn part1(input: &Vec::<u32>) -> Option<Answer> {
+        Some(count_windows(input, 1, Compare::Increase).into())
+    }
+
+    fn part2(input: &Vec::<u32>) -> Option<Answer> {
+        Some(count_windows(input, 3, Compare::Increase).into())
+    }
+
+    fn variants() -> Vec::<Variant<Vec::<u32>>> {
+        vec![
+            Variant {
+                name: "summing the windows",
+                part: Part::Two,
+                solve: |input| Some(summing_windows(input, 3, Compare::Increase).into()),
+                work: |input| input.len() as u128 * 3,
+            },
+        ]
     }
-    
-    number_of_increases
 }
 
-fn main() -> Result<(), Error> {
-    let filepath = "./input.txt";
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{property, GenOptions};
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day01>(TEST_INPUT, 7, 5);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day01>(&input, 7, 5);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day01>(INPUT, 1553, 1597);
+    }
+
+    #[test]
+    fn bad_depth_is_reported() {
+        let error = Day01::parse("199\n2O0\n208").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "2O0"));
+    }
+
+    #[test]
+    fn counting_windows_matches_summing_them() {
+        // Small depths so that plenty of windows have the same sum
+        let depths = property::vecs(property::numbers(0..=20), 0..=30);
+        let cases = property::pairs(depths, property::numbers(1..=6));
+
+        property::check(5000, &cases, |(depths, window)| {
+            let depths: Vec::<u32> = depths.iter().map(|d| *d as u32).collect();
+
+            [Compare::Increase, Compare::Decrease, Compare::Equal].into_iter().all(|compare| {
+                let window = *window as usize;
+                count_windows(&depths, window, compare) == summing_windows(&depths, window, compare)
+            })
+        });
+    }
+
+    #[test]
+    fn too_few_depths_have_no_windows_to_compare() {
+        assert_eq!(count_windows(&[], 1, Compare::Increase), 0);
+        assert_eq!(count_windows(&[1, 2, 3], 3, Compare::Increase), 0);
+        assert_eq!(count_windows(&[1, 2, 3, 4], 3, Compare::Increase), 1);
+        assert_eq!(count_windows(&[1, 2, 3], 0, Compare::Equal), 0);
+    }
 
-    let file_contents = fs::read_to_string(filepath)?;
+    #[test]
+    fn windows_can_decrease_or_stay_equal() {
+        let depths = Day01::parse(TEST_INPUT).unwrap();
 
-    let file_contents_vec: Vec::<u32> = file_contents
-        .split("\n")
-        .map(|s| s.parse().expect("Failed to convert &str to u32"))
-        .collect();
+        assert_eq!(count_windows(&depths, 1, Compare::Decrease), 2);
+        assert_eq!(count_windows(&depths, 3, Compare::Equal), 1);
+        assert_eq!(count_windows(&depths, 3, Compare::Decrease), 1);
+    }
 
-    // we do immutable borrows of our file_contents_vec 
-    // so we can use it multiple times
-    println!("Number of increases = {}", number_of_increases(&file_contents_vec));
-    println!("Sliding window increases = {}", sliding_window(&file_contents_vec));
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day01>(&[TEST_INPUT], 2000);
+    }
 
-    Ok(())
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day01>(seed, &GenOptions::default());
+        }
+    }
 }
@@ -0,0 +1,155 @@
+use std::fmt;
+
+// Smoothing out a noisy sonar sweep before its windows are compared, so that
+// a depth wobbling by a unit or two doesn't count as getting deeper. Every
+// filter gives back as many depths as it was given, so filters can be chained
+// and the windows of the filtered depths line up with the raw ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    // Each depth becomes the median of the window of depths around it
+    Median(usize),
+    // Each depth becomes the mean of the window of depths around it, rounded
+    // to the nearest whole depth
+    Mean(usize),
+    // A depth only moves once it has changed by more than this much since it
+    // last moved, so smaller changes come out as the same depth
+    Hysteresis(u32),
+}
+
+impl Filter {
+    // e.g. "median:3", "mean:5" or "hysteresis:2". The windows need at
+    // least one depth in them.
+    pub fn from_spec(spec: &str) -> Option<Filter> {
+        let (name, size) = spec.split_once(':')?;
+
+        match name {
+            "median" => size.parse().ok().filter(|k| *k > 0).map(Filter::Median),
+            "mean" => size.parse().ok().filter(|k| *k > 0).map(Filter::Mean),
+            "hysteresis" => size.parse().ok().map(Filter::Hysteresis),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, depths: &[u32]) -> Vec::<u32> {
+        match self {
+            Filter::Median(window) => around(depths, window, |w| {
+                let mut w = w.to_vec();
+                w.sort_unstable();
+                w[(w.len() - 1) / 2]
+            }),
+            Filter::Mean(window) => around(depths, window, |w| {
+                let sum: u64 = w.iter().map(|d| *d as u64).sum();
+                ((sum + w.len() as u64 / 2) / w.len() as u64) as u32
+            }),
+            Filter::Hysteresis(threshold) => {
+                let mut held = None;
+                depths
+                    .iter()
+                    .map(|depth| {
+                        let moved = held.is_none_or(|held: u32| depth.abs_diff(held) > threshold);
+                        if moved {
+                            held = Some(*depth);
+                        }
+                        held.unwrap()
+                    })
+                    .collect()
+            },
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Median(window) => write!(f, "median:{}", window),
+            Filter::Mean(window) => write!(f, "mean:{}", window),
+            Filter::Hysteresis(threshold) => write!(f, "hysteresis:{}", threshold),
+        }
+    }
+}
+
+// Maps each depth to some summary of the window centred on it. Near the ends
+// of the sweep the window is cut short rather than padded out with depths
+// that were never read.
+fn around(depths: &[u32], window: usize, summary: impl Fn(&[u32]) -> u32) -> Vec::<u32> {
+    let before = (window - 1) / 2;
+    let after = window / 2;
+
+    (0..depths.len())
+        .map(|i| summary(&depths[i.saturating_sub(before)..(i + after + 1).min(depths.len())]))
+        .collect()
+}
+
+// Runs the depths through each of the filters in turn
+pub fn filter(depths: &[u32], filters: &[Filter]) -> Vec::<u32> {
+    filters.iter().fold(depths.to_vec(), |depths, filter| {
+        let filtered = filter.apply(&depths);
+        aoc::trace::event(|| {
+            let changed = depths.iter().zip(&filtered).filter(|(a, b)| a != b).count();
+            format!("{} changed {} of {} depths", filter, changed, depths.len())
+        });
+        filtered
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{count_windows, Compare};
+    use aoc::property;
+
+    #[test]
+    fn filters_are_read_from_specs() {
+        assert_eq!(Filter::from_spec("median:3"), Some(Filter::Median(3)));
+        assert_eq!(Filter::from_spec("hysteresis:0"), Some(Filter::Hysteresis(0)));
+        assert_eq!(Filter::from_spec("mean:0"), None);
+        assert_eq!(Filter::from_spec("mode:3"), None);
+        assert_eq!(Filter::from_spec("median"), None);
+        assert_eq!(Filter::Mean(5).to_string(), "mean:5");
+    }
+
+    #[test]
+    fn spikes_are_smoothed_out() {
+        let depths = [100, 101, 100, 150, 100, 101, 102];
+
+        assert_eq!(Filter::Median(3).apply(&depths), vec![100, 100, 101, 100, 101, 101, 101]);
+        assert_eq!(Filter::Mean(3).apply(&depths), vec![101, 100, 117, 117, 117, 101, 102]);
+        assert_eq!(Filter::Hysteresis(1).apply(&depths), vec![100, 100, 100, 150, 100, 100, 102]);
+    }
+
+    #[test]
+    fn hysteresis_ignores_small_wobbles() {
+        let depths = [200, 201, 200, 201, 202, 203, 210];
+        let filtered = filter(&depths, &[Filter::Hysteresis(2)]);
+
+        assert_eq!(count_windows(&depths, 1, Compare::Increase), 5);
+        assert_eq!(count_windows(&filtered, 1, Compare::Increase), 2);
+    }
+
+    #[test]
+    fn windows_of_one_and_no_threshold_change_nothing() {
+        let depths = property::vecs(property::numbers(0..=u32::MAX as u64), 0..=30);
+
+        property::check(2000, &depths, |depths| {
+            let depths: Vec::<u32> = depths.iter().map(|d| *d as u32).collect();
+            let filters = [Filter::Median(1), Filter::Mean(1), Filter::Hysteresis(0)];
+
+            filter(&depths, &filters) == depths && filter(&depths, &[]) == depths
+        });
+    }
+
+    #[test]
+    fn filtered_changes_are_never_below_the_threshold() {
+        let depths = property::vecs(property::numbers(0..=20), 0..=30);
+        let cases = property::pairs(depths, property::numbers(0..=5));
+
+        property::check(2000, &cases, |(depths, threshold)| {
+            let depths: Vec::<u32> = depths.iter().map(|d| *d as u32).collect();
+            let threshold = *threshold as u32;
+            let filtered = Filter::Hysteresis(threshold).apply(&depths);
+
+            filtered.len() == depths.len()
+                && filtered.windows(2).all(|pair| pair[0] == pair[1] || pair[0].abs_diff(pair[1]) > threshold)
+        });
+    }
+}
@@ -0,0 +1,33 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day01;
+
+// A sonar sweep which wanders up and down like the real one, going deeper
+// more often than not
+impl Generator for Day01 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(2000);
+
+        let mut depth: u64 = rng.range(100..200);
+        let mut depths = Vec::<u64>::new();
+
+        for _ in 0..size {
+            depths.push(depth);
+            depth = (depth + rng.range(0..40)).saturating_sub(15);
+        }
+
+        // Comparing windows of three directly, rather than using the trick
+        // that the middle two depths are shared
+        let increases = depths.windows(2).filter(|pair| pair[1] > pair[0]).count();
+        let window_sums: Vec::<u64> = depths.windows(3).map(|window| window.iter().sum()).collect();
+        let window_increases = window_sums.windows(2).filter(|pair| pair[1] > pair[0]).count();
+
+        let input: Vec::<String> = depths.iter().map(|depth| depth.to_string()).collect();
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(increases.into()),
+            part2: Some(window_increases.into()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex01::Day01;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day01>(FILEPATH)
+}
@@ -0,0 +1,181 @@
+// Everything else worth knowing about a sonar sweep besides how often it gets
+// deeper. Indices are into the depths, starting from 0.
+
+// A stretch of depths, e.g. the longest run of them getting deeper
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub start: usize,
+    // How many depths are in the run, rather than how many steps
+    pub len: usize,
+}
+
+// The change from one depth to the next, where index is the later depth
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub index: usize,
+    pub change: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub depths: usize,
+    pub longest_increase: Option<Run>,
+    pub longest_decrease: Option<Run>,
+    // Every run of two or more depths that are the same
+    pub plateaus: Vec::<Run>,
+    pub largest_jump: Option<Step>,
+    pub largest_drop: Option<Step>,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub mean: Option<f64>,
+}
+
+// Every maximal run where each depth is related to the one before it by
+// keep, with at least two depths in it
+fn runs(depths: &[u32], keep: impl Fn(u32, u32) -> bool) -> Vec::<Run> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+
+    for i in 1..=depths.len() {
+        if i < depths.len() && keep(depths[i - 1], depths[i]) {
+            continue
+        }
+
+        if i - start >= 2 {
+            runs.push(Run { start, len: i - start });
+        }
+        start = i;
+    }
+
+    runs
+}
+
+// The first of the longest runs, if there are any at all
+fn longest(runs: Vec::<Run>) -> Option<Run> {
+    runs.into_iter().rev().max_by_key(|run| run.len)
+}
+
+pub fn report(depths: &[u32]) -> Report {
+    let steps = || {
+        depths
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| Step { index: i + 1, change: pair[1] as i64 - pair[0] as i64 })
+    };
+
+    // Ties go to the first, as with the runs. max_by_key gives the last of
+    // them, so the jumps are searched backwards, while min_by_key already
+    // gives the first drop.
+    let largest_jump = steps().filter(|step| step.change > 0).rev().max_by_key(|step| step.change);
+    let largest_drop = steps().filter(|step| step.change < 0).min_by_key(|step| step.change);
+
+    let total: u64 = depths.iter().map(|depth| *depth as u64).sum();
+
+    Report {
+        depths: depths.len(),
+        longest_increase: longest(runs(depths, |a, b| b > a)),
+        longest_decrease: longest(runs(depths, |a, b| b < a)),
+        plateaus: runs(depths, |a, b| b == a),
+        largest_jump,
+        largest_drop,
+        min: depths.iter().copied().min(),
+        max: depths.iter().copied().max(),
+        mean: (!depths.is_empty()).then(|| total as f64 / depths.len() as f64),
+    }
+}
+
+// The mean of each window of depths, starting from the window at the first
+// depth. Rather than adding up every window, a running sum has the depth
+// joining the window added and the depth leaving it taken away, so this is
+// O(n) whatever the size of the window. There are no windows of 0 depths,
+// or bigger than the sweep.
+pub fn windowed_means(depths: &[u32], window: usize) -> Vec::<f64> {
+    if window == 0 || window > depths.len() {
+        return Vec::new()
+    }
+
+    let mut sum: u64 = depths[..window].iter().map(|depth| *depth as u64).sum();
+    let mut means = vec![sum as f64 / window as f64];
+
+    for i in window..depths.len() {
+        sum = sum + depths[i] as u64 - depths[i - window] as u64;
+        means.push(sum as f64 / window as f64);
+    }
+
+    means
+}
+
+// The windowed means as CSV, one row per window with the indices of the first
+// and last depths in it
+pub fn means_csv(depths: &[u32], window: usize) -> String {
+    let mut csv = String::from("start,end,mean\n");
+
+    for (start, mean) in windowed_means(depths, window).iter().enumerate() {
+        csv += &format!("{},{},{}\n", start, start + window - 1, mean);
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::property;
+
+    const DEPTHS: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn example_report() {
+        let report = report(&DEPTHS);
+
+        assert_eq!(report.longest_increase, Some(Run { start: 0, len: 4 }));
+        assert_eq!(report.longest_decrease, Some(Run { start: 3, len: 2 }));
+        assert_eq!(report.plateaus, vec![]);
+        assert_eq!(report.largest_jump, Some(Step { index: 6, change: 33 }));
+        assert_eq!(report.largest_drop, Some(Step { index: 4, change: -10 }));
+        assert_eq!((report.min, report.max, report.mean), (Some(199), Some(269), Some(225.6)));
+    }
+
+    #[test]
+    fn plateaus_and_ties_go_to_the_first() {
+        let report = report(&[5, 5, 5, 1, 2, 2, 0, 3]);
+
+        assert_eq!(report.plateaus, vec![Run { start: 0, len: 3 }, Run { start: 4, len: 2 }]);
+        assert_eq!(report.longest_increase, Some(Run { start: 3, len: 2 }));
+        assert_eq!(report.largest_drop, Some(Step { index: 3, change: -4 }));
+    }
+
+    #[test]
+    fn empty_and_flat_sweeps_have_nothing_to_report() {
+        let empty = report(&[]);
+        assert_eq!((empty.longest_increase, empty.largest_jump, empty.min, empty.mean), (None, None, None, None));
+
+        let flat = report(&[7]);
+        assert_eq!((flat.longest_decrease, flat.largest_drop, flat.mean), (None, None, Some(7.0)));
+    }
+
+    #[test]
+    fn means_are_exported_as_csv() {
+        assert_eq!(means_csv(&[1, 2, 3, 6], 2), "start,end,mean\n0,1,1.5\n1,2,2.5\n2,3,4.5\n");
+        assert_eq!(means_csv(&[1, 2], 3), "start,end,mean\n");
+    }
+
+    #[test]
+    fn running_sums_match_adding_up_each_window() {
+        let depths = property::vecs(property::numbers(0..=u32::MAX as u64), 0..=20);
+        let cases = property::pairs(depths, property::numbers(0..=6));
+
+        property::check(2000, &cases, |(depths, window)| {
+            let depths: Vec::<u32> = depths.iter().map(|d| *d as u32).collect();
+            let window = *window as usize;
+
+            let mean = |w: &[u32]| w.iter().map(|d| *d as u64).sum::<u64>() as f64 / window as f64;
+            let added_up: Vec::<f64> = match window {
+                0 => Vec::new(),
+                _ => depths.windows(window).map(mean).collect(),
+            };
+
+            windowed_means(&depths, window) == added_up
+        });
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+use crate::Compare;
+
+// Counting windows as the depths come in, for a sonar sweep that's too long
+// (or never ends) to read in all at once. Only the last few depths are kept,
+// as many as the biggest window, since comparing two windows only needs the
+// depth leaving the earlier one and the depth joining the later one.
+
+// The counts so far, after each depth
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Running {
+    // How many depths there have been, including this one
+    pub depths: usize,
+    pub depth: u32,
+    // One count for each of the window sizes, in the same order
+    pub counts: Vec::<usize>,
+}
+
+pub struct Sweep<I> {
+    depths: I,
+    windows: Vec::<usize>,
+    compare: Compare,
+    recent: VecDeque::<u32>,
+    seen: usize,
+    counts: Vec::<usize>,
+}
+
+impl<I> Sweep<I> {
+    pub fn windows(&self) -> &[usize] {
+        &self.windows
+    }
+}
+
+impl<I: Iterator<Item = u32>> Iterator for Sweep<I> {
+    type Item = Running;
+
+    fn next(&mut self) -> Option<Running> {
+        let depth = self.depths.next()?;
+
+        for (window, count) in self.windows.iter().zip(self.counts.iter_mut()) {
+            // A window of 0 depths never counts, as with count_windows
+            if *window == 0 || *window > self.recent.len() {
+                continue
+            }
+
+            if self.compare.matches(self.recent[self.recent.len() - window], depth) {
+                *count += 1;
+            }
+        }
+
+        self.recent.push_back(depth);
+        if self.recent.len() > self.windows.iter().copied().max().unwrap_or(0) {
+            self.recent.pop_front();
+        }
+
+        self.seen += 1;
+        Some(Running { depths: self.seen, depth, counts: self.counts.clone() })
+    }
+}
+
+// Adds `sweep` to any iterator of depths, e.g.
+//   depths.sweep(&[1, 3], Compare::Increase).last()
+// gives the answers to both parts.
+pub trait SweepExt: Iterator<Item = u32> + Sized {
+    fn sweep(self, windows: &[usize], compare: Compare) -> Sweep<Self> {
+        Sweep {
+            depths: self,
+            windows: windows.to_vec(),
+            compare,
+            recent: VecDeque::new(),
+            seen: 0,
+            counts: vec![0; windows.len()],
+        }
+    }
+}
+
+impl<I: Iterator<Item = u32>> SweepExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::count_windows;
+    use aoc::property;
+
+    #[test]
+    fn running_counts_build_up() {
+        let running: Vec::<Vec::<usize>> = [199, 200, 208, 210, 200]
+            .into_iter()
+            .sweep(&[1, 3], Compare::Increase)
+            .map(|running| running.counts)
+            .collect();
+
+        assert_eq!(running, vec![vec![0, 0], vec![1, 0], vec![2, 0], vec![3, 1], vec![3, 1]]);
+    }
+
+    #[test]
+    fn streaming_matches_counting_the_whole_sweep() {
+        let depths = property::vecs(property::numbers(0..=20), 0..=30);
+        let windows = [0, 1, 2, 3, 7];
+
+        property::check(2000, &depths, |depths| {
+            let depths: Vec::<u32> = depths.iter().map(|d| *d as u32).collect();
+
+            [Compare::Increase, Compare::Decrease, Compare::Equal].into_iter().all(|compare| {
+                let last = depths.iter().copied().sweep(&windows, compare).last();
+                let counts = last.map_or(vec![0; windows.len()], |running| running.counts);
+
+                windows.iter().zip(counts).all(|(window, count)| count == count_windows(&depths, *window, compare))
+            })
+        });
+    }
+
+    #[test]
+    fn only_the_biggest_window_is_kept() {
+        let mut sweep = (0..1000_u32).sweep(&[2, 5], Compare::Increase);
+        sweep.by_ref().for_each(drop);
+
+        assert_eq!(sweep.recent.len(), 5);
+        assert_eq!(sweep.windows(), [2, 5]);
+    }
+}
@@ -1,109 +1,183 @@
-use std::fs;
-use std::io::Error;
+use aoc::{parser, Answer, Details, ParseError, Solution};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
+mod script;
+mod trajectory;
 
-fn generate_input_vec() -> Result<Vec::<Move>, Error> {
-    let file_contents = fs::read_to_string(FILEPATH)?;
+pub use script::{parse_script, Command, Script};
+pub use trajectory::{Model, Position, Stop, Trajectory, MAX_MOVES};
 
-    let moves_vec: Vec::<Move> = file_contents
-        .split("\n")
-        .map(|x| {
-            let y: Vec::<&str> = x.split(" ").collect();
-            
-            let direction_str: &str = y[0];
-            let distance: i32 = y[1].parse().expect("Failed to cast &str to i32");
+fn generate_input_vec(input: &str) -> Result<Vec::<Move>, ParseError> {
+    // e.g. "forward 5"
+    let direction = parser::try_map(parser::token("forward, up or down"), "forward, up or down", parse_direction);
+    let distance = parser::integer("a distance");
+    let line = parser::separated_pair(direction, parser::whitespace(), distance);
 
-            build_move(direction_str, distance)
-        })
-        .collect();
-
-    Ok(moves_vec)
+    parser::parse_lines(input, parser::map(line, |(direction, distance)| Move { direction, distance }))
 }
 
-// We construct each line in input.txt to a Move
-enum Direction {
+// We construct each line in input.txt to a Move. Back only comes from
+// scripts, it isn't in the puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
     Forward,
     Up,
     Down,
+    Back,
 }
 
-struct Move {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
     direction: Direction,
     distance: i32,
 }
 
-fn build_move(direction_str: &str, distance: i32) -> Move {
-    let direction = match direction_str {
-        "forward" => Direction::Forward,
-        "up" => Direction::Up,
-        "down" => Direction::Down,
-        _ => panic!("Unknown direction in input.txt!")
-    };
+// Returns None for an unknown direction
+fn parse_direction(direction_str: &str) -> Option<Direction> {
+    match direction_str {
+        "forward" => Some(Direction::Forward),
+        "up" => Some(Direction::Up),
+        "down" => Some(Direction::Down),
+        _ => None
+    }
+}
 
-    Move { direction, distance }
+// Used from the sum of all of the moves. The distances are i32s, but there
+// can be enough of them to overflow one, so we add them up as i64s.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Displacement {
+    pub horizontal: i64,
+    pub vertical: i64,
 }
 
-// Used from the sum of all of the moves
-struct Displacement {
-    horizontal: i32,
-    vertical: i32,
+// Part 1 - simply adding all of the moves. The moves can come from anywhere,
+// e.g. a script running them as it goes.
+pub fn get_displacement<'a>(moves_vec: impl IntoIterator<Item = &'a Move>) -> Option<Displacement> {
+    final_displacement(Model::Plain, moves_vec)
 }
 
-// Part 1 - simply adding all of the moves
-fn get_displacement(moves_vec: &Vec::<Move>) -> Displacement {
-    let mut horizontal_displacement: i32 = 0;
-    let mut vertical_displacement: i32 = 0;
+// Part 2: use a concept of Aim where up and down are effectively angling the submarine.
+// When the depth gets too big to work out there's no answer.
+pub fn get_aim_displacement<'a>(moves_vec: impl IntoIterator<Item = &'a Move>) -> Option<Displacement> {
+    final_displacement(Model::Aim, moves_vec)
+}
 
-    for i in 0..moves_vec.len() {
-        match moves_vec[i].direction {
-            Direction::Forward => horizontal_displacement += moves_vec[i].distance,
-            Direction::Down => vertical_displacement += moves_vec[i].distance,
-            Direction::Up => vertical_displacement -+ moves_vec[i].distance,
-        }
-    }
+// Where the moves end up, without keeping every position along the way like
+// a Trajectory does
+fn final_displacement<'a>(model: Model, moves_vec: impl IntoIterator<Item = &'a Move>) -> Option<Displacement> {
+    let mut position = Position::default();
 
-    Displacement {
-        horizontal: horizontal_displacement,
-        vertical: vertical_displacement,
+    for next_move in moves_vec {
+        position = model.step(position, next_move)?;
+        aoc::trace::event(|| {
+            format!("{:?} {}: {}", next_move.direction, next_move.distance, model.describe(&position))
+        });
     }
+
+    Some(Displacement {
+        horizontal: position.horizontal,
+        vertical: position.depth,
+    })
 }
 
-// Part 2: use a concept of Aim where up and down are effectively angling the submarine
-fn get_aim_displacement(moves_vec: &[Move]) -> Displacement {
-    let mut horizontal_displacement: i32 = 0;
-    let mut vertical_displacement: i32 = 0;
-    let mut aim: i32 = 0;
-
-    for i in 0..moves_vec.len() {
-        match moves_vec[i].direction {
-            Direction::Forward => {
-                horizontal_displacement += moves_vec[i].distance;
-                vertical_displacement += (aim * moves_vec[i].distance);
-            },
-            Direction::Down => aim += moves_vec[i].distance,
-            Direction::Up => aim -= moves_vec[i].distance,
-        }
+impl Displacement {
+    // The product can be bigger than either displacement, e.g. for the
+    // longer generated courses
+    fn multiplied(&self) -> Answer {
+        (self.horizontal as i128 * self.vertical as i128).into()
     }
 
-    Displacement {
-        horizontal: horizontal_displacement,
-        vertical: vertical_displacement,
+    fn details(&self) -> Details {
+        vec![
+            ("horizontal", self.horizontal.into()),
+            ("vertical", self.vertical.into()),
+        ]
     }
 }
 
-fn print_displacement_vals(displacement: Displacement) {
-    println!("Horizontal Displacement = {}", displacement.horizontal);
-    println!("Vertical Displacement = {}", displacement.vertical);
-    println!("Multipled, this gives {}", displacement.horizontal * displacement.vertical);
-    println!("");
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec::<Move>;
+
+    fn parse(input: &str) -> Result<Vec::<Move>, ParseError> {
+        generate_input_vec(input)
+    }
+
+    fn part1(moves_vec: &Vec::<Move>) -> Option<Answer> {
+        get_displacement(moves_vec).map(|displacement| displacement.multiplied())
+    }
+
+    fn part2(moves_vec: &Vec::<Move>) -> Option<Answer> {
+        get_aim_displacement(moves_vec).map(|displacement| displacement.multiplied())
+    }
+
+    fn part1_details(moves_vec: &Vec::<Move>) -> Details {
+        get_displacement(moves_vec).map_or_else(Vec::new, |displacement| displacement.details())
+    }
+
+    fn part2_details(moves_vec: &Vec::<Move>) -> Details {
+        get_aim_displacement(moves_vec).map_or_else(Vec::new, |displacement| displacement.details())
+    }
 }
 
-fn main() -> Result<(), Error> {
-    let moves_vec = generate_input_vec()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
 
-    print_displacement_vals(get_displacement(&moves_vec));
-    print_displacement_vals(get_aim_displacement(&moves_vec));
-        
-    Ok(())
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day02>(TEST_INPUT, 150, 900);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day02>(&input, 150, 900);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day02>(INPUT, 1561344, 1848454425);
+    }
+
+    // up used to be a no-op (`-+` instead of `-=`) which this catches
+    #[test]
+    fn up_reduces_depth() {
+        aoc::assert_answers::<Day02>("down 5\nup 3\nforward 2", 4, 8);
+    }
+
+    #[test]
+    fn unknown_direction_is_reported() {
+        let error = Day02::parse("forward 5\nbackward 3").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "backward"));
+    }
+
+    #[test]
+    fn long_moves_dont_overflow() {
+        let moves = Day02::parse("forward 2147483647\nforward 2147483647\ndown 2147483647").unwrap();
+        assert_eq!(Day02::part1(&moves), Some((4294967294_i64 * 2147483647).into()));
+    }
+
+    #[test]
+    fn depths_too_deep_to_work_out_have_no_answer() {
+        let course = ["down 2147483647", "forward 2147483647"].repeat(3).join("\n");
+        assert_eq!(Day02::part2(&Day02::parse(&course).unwrap()), None);
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day02>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day02>(seed, &GenOptions::default());
+        }
+    }
 }
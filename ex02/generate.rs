@@ -0,0 +1,51 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day02;
+
+// A course of moves like the real one, with distances from 1 to 9. Downwards
+// moves are more likely than upwards ones so that we don't fly out of the
+// sea, since the depth can't go above the surface.
+impl Generator for Day02 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(1000);
+
+        // Both movement models side by side
+        let (mut horizontal, mut depth) = (0i64, 0i64);
+        let (mut aim, mut aimed_depth) = (0i64, 0i64);
+
+        let mut input = Vec::<String>::new();
+
+        for _ in 0..size {
+            let distance = rng.range(1..10) as i64;
+            let direction = match rng.range(0..10) {
+                0..=3 => "forward",
+                4..=7 => "down",
+                _ if depth >= distance => "up",
+                _ => "down",
+            };
+
+            match direction {
+                "forward" => {
+                    horizontal += distance;
+                    aimed_depth += aim * distance;
+                },
+                "down" => {
+                    depth += distance;
+                    aim += distance;
+                },
+                _ => {
+                    depth -= distance;
+                    aim -= distance;
+                },
+            }
+
+            input.push(format!("{} {}", direction, distance));
+        }
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some((horizontal * depth).into()),
+            part2: Some((horizontal * aimed_depth).into()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex02::Day02;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day02>(FILEPATH)
+}
@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+
+use aoc::parser::{self, Cursor, Parser};
+use aoc::ParseError;
+
+use crate::{get_aim_displacement, get_displacement, Direction, Displacement, Move, MAX_MOVES};
+
+// A little language for piloting the submarine, a superset of the puzzle
+// input so that any input is also a script:
+//
+//   # anything after a # is a comment
+//   macro zigzag {
+//       down 2
+//       forward 1
+//       up 2
+//   }
+//   repeat 3 {
+//       zigzag
+//       back 1
+//   }
+//   forward 5
+//
+// A macro has to be defined, at the top level, before it's used, which also
+// means a macro can never end up calling itself. Scripts are run a move at a
+// time rather than being expanded, so a repeat can go round as many times as
+// it likes without the moves all being kept in memory.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Move(Move),
+    Repeat { times: u64, body: Block },
+    // Runs the body of the macro with this name
+    Call(String),
+}
+
+// The commands of a script, a repeat or a macro. Blocks that don't make any
+// moves are skipped when the script is run, so that repeating nothing a
+// billion times doesn't take a billion steps, and whether they do is worked
+// out once when they're parsed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Block {
+    pub commands: Vec::<Command>,
+    pub moves_anything: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Script {
+    pub macros: HashMap<String, Block>,
+    pub commands: Block,
+}
+
+// What a line of a script can be. The text is kept for the errors.
+enum Statement<'a> {
+    Move(Move),
+    Repeat(u64),
+    Macro { keyword: &'a str, name: &'a str },
+    Call(&'a str),
+    Close(&'a str),
+}
+
+const COMMAND: &str = "forward, up, down, back, repeat, macro or the name of a macro";
+const KEYWORDS: [&str; 6] = ["forward", "up", "down", "back", "repeat", "macro"];
+
+// Anything that isn't a command has to be one of the macros defined so far
+fn statement<'a, 'm>(macros: &'m HashMap<String, Block>) -> impl Parser<'a, Statement<'a>> + 'm {
+    move |cursor: Cursor<'a>| {
+        if let Ok((_, rest)) = parser::literal("}")(cursor) {
+            return Ok((Statement::Close(&cursor.rest[..1]), rest))
+        }
+
+        let (word, rest) = parser::token(COMMAND)(cursor)?;
+        match word {
+            "forward" | "up" | "down" | "back" | "repeat" | "macro" => command(word, rest),
+            _ if macros.contains_key(word) => Ok((Statement::Call(word), rest)),
+            _ => Err(cursor.error(word, COMMAND)),
+        }
+    }
+}
+
+// The rest of a line starting with one of the keywords
+fn command<'a>(word: &'a str, rest: Cursor<'a>) -> parser::Parsed<'a, Statement<'a>> {
+    let open = || parser::padded(parser::literal("{"));
+
+    match word {
+        "forward" | "up" | "down" | "back" => {
+            let direction = match word {
+                "forward" => Direction::Forward,
+                "up" => Direction::Up,
+                "down" => Direction::Down,
+                _ => Direction::Back,
+            };
+
+            let distance = parser::preceded(parser::whitespace(), parser::integer("a distance"));
+            parser::map(distance, move |distance| Statement::Move(Move { direction, distance }))(rest)
+        },
+        "repeat" => {
+            let times = parser::preceded(parser::whitespace(), parser::integer("a number of times"));
+            parser::map(parser::terminated(times, open()), Statement::Repeat)(rest)
+        },
+        "macro" => {
+            let name = parser::verify(parser::token("a macro name"), "a macro name", |name| is_name(name));
+            let name = parser::preceded(parser::whitespace(), name);
+            parser::map(parser::terminated(name, open()), |name| Statement::Macro { keyword: word, name })(rest)
+        },
+        _ => unreachable!(),
+    }
+}
+
+// Letters and digits, starting with a letter, and not one of the commands
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+        && !KEYWORDS.contains(&name)
+}
+
+// The line up to any comment
+fn code(line: &str) -> &str {
+    line.find('#').map_or(line, |comment| &line[..comment])
+}
+
+pub fn parse_script(source: &str) -> Result<Script, ParseError> {
+    let mut lines = aoc::input::lines(source)
+        .map(|(i, line)| (i, code(line)))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let mut macros = HashMap::new();
+    let commands = parse_block(&mut lines, &mut macros, None)?;
+
+    Ok(Script { macros, commands })
+}
+
+// Parses the commands up to the } that closes the block opened on the line
+// `opened`, or up to the end of the script at the top level
+fn parse_block<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    macros: &mut HashMap<String, Block>,
+    opened: Option<(usize, &'a str)>,
+) -> Result<Block, ParseError> {
+    let mut commands = Vec::new();
+
+    while let Some((i, line)) = lines.next() {
+        match parser::parse_line(i, line, parser::padded(statement(macros)))? {
+            Statement::Move(next_move) => commands.push(Command::Move(next_move)),
+            Statement::Repeat(times) => {
+                let body = parse_block(lines, macros, Some((i, line)))?;
+                commands.push(Command::Repeat { times, body });
+            },
+            Statement::Macro { keyword, name } => {
+                if opened.is_some() {
+                    let expected = "a command, macros are only defined at the top level";
+                    return Err(ParseError::new(i, line, keyword, expected))
+                }
+
+                if macros.contains_key(name) {
+                    return Err(ParseError::new(i, line, name, "the name of a macro that isn't defined yet"))
+                }
+
+                let body = parse_block(lines, macros, Some((i, line)))?;
+                macros.insert(name.to_string(), body);
+            },
+            Statement::Call(name) => commands.push(Command::Call(name.to_string())),
+            Statement::Close(close) => {
+                if opened.is_none() {
+                    return Err(ParseError::new(i, line, close, "a command, there's no block to close"))
+                }
+
+                return Ok(Block::new(commands, macros))
+            },
+        }
+    }
+
+    match opened {
+        Some((i, line)) => {
+            Err(ParseError::new(i, line, line.trim(), "a } to close this before the end of the script"))
+        },
+        None => Ok(Block::new(commands, macros)),
+    }
+}
+
+impl Block {
+    // The blocks inside of these commands, and the macros they call, have
+    // already been parsed so this doesn't need to look any deeper
+    fn new(commands: Vec::<Command>, macros: &HashMap<String, Block>) -> Block {
+        let moves_anything = commands.iter().any(|command| match command {
+            Command::Move(_) => true,
+            Command::Repeat { times, body } => *times > 0 && body.moves_anything,
+            Command::Call(name) => macros[name].moves_anything,
+        });
+
+        Block { commands, moves_anything }
+    }
+}
+
+impl Script {
+    // Every move the script makes, in order
+    pub fn moves(&self) -> Moves<'_> {
+        let mut moves = Moves { script: self, stack: Vec::new() };
+        moves.enter(&self.commands, 1);
+        moves
+    }
+
+    // Where the script ends up. Like a trajectory, a script making more than
+    // MAX_MOVES moves is given up on, so there's no displacement for it.
+    pub fn displacement(&self) -> Option<Displacement> {
+        get_displacement(self.limited_moves()?)
+    }
+
+    pub fn aim_displacement(&self) -> Option<Displacement> {
+        get_aim_displacement(self.limited_moves()?)
+    }
+
+    fn limited_moves(&self) -> Option<Moves<'_>> {
+        self.moves().nth(MAX_MOVES).is_none().then(|| self.moves())
+    }
+}
+
+// Where the script has got to in each of the blocks it's inside of
+struct Frame<'a> {
+    commands: &'a [Command],
+    next: usize,
+    // Including the time round it's on now
+    times_left: u64,
+}
+
+pub struct Moves<'a> {
+    script: &'a Script,
+    stack: Vec::<Frame<'a>>,
+}
+
+impl<'a> Moves<'a> {
+    fn enter(&mut self, block: &'a Block, times: u64) {
+        if times > 0 && block.moves_anything {
+            self.stack.push(Frame { commands: &block.commands, next: 0, times_left: times });
+        }
+    }
+}
+
+impl<'a> Iterator for Moves<'a> {
+    type Item = &'a Move;
+
+    fn next(&mut self) -> Option<&'a Move> {
+        loop {
+            let frame = self.stack.last_mut()?;
+
+            if frame.next == frame.commands.len() {
+                frame.times_left -= 1;
+                frame.next = 0;
+                if frame.times_left == 0 {
+                    self.stack.pop();
+                }
+                continue
+            }
+
+            let command = &frame.commands[frame.next];
+            frame.next += 1;
+
+            match command {
+                Command::Move(next_move) => return Some(next_move),
+                Command::Repeat { times, body } => self.enter(body, *times),
+                Command::Call(name) => self.enter(&self.script.macros[name], 1),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day02;
+    use aoc::Solution;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+
+    fn displacements(source: &str) -> (Option<Displacement>, Option<Displacement>) {
+        let script = parse_script(source).unwrap();
+        (script.displacement(), script.aim_displacement())
+    }
+
+    fn at(horizontal: i64, vertical: i64) -> Option<Displacement> {
+        Some(Displacement { horizontal, vertical })
+    }
+
+    fn error_at(source: &str) -> (usize, usize, String, String) {
+        let error = parse_script(source).unwrap_err();
+        (error.line, error.column, error.text, error.expected)
+    }
+
+    #[test]
+    fn puzzle_inputs_are_scripts() {
+        let moves = Day02::parse(TEST_INPUT).unwrap();
+        let script = parse_script(TEST_INPUT).unwrap();
+
+        assert_eq!(script.moves().collect::<Vec::<_>>(), moves.iter().collect::<Vec::<_>>());
+        assert_eq!(displacements(TEST_INPUT), (at(15, 10), at(15, 60)));
+    }
+
+    #[test]
+    fn comments_repeats_and_macros() {
+        let source = "\
+            # a zigzag down and back up\n\
+            macro zigzag {\n\
+                down 2   # on the way down\n\
+                forward 1\n\
+                up 2\n\
+            }\n\
+            \n\
+            repeat 3 {\n\
+                zigzag\n\
+                repeat 2 { \n\
+                    forward 1\n\
+                }\n\
+            }\n\
+            down 4";
+
+        assert_eq!(displacements(source), (at(9, 4), at(9, 6)));
+    }
+
+    #[test]
+    fn going_back_undoes_going_forward() {
+        assert_eq!(displacements("down 3\nforward 5\nback 2"), (at(3, 3), at(3, 9)));
+    }
+
+    #[test]
+    fn repeats_run_without_expanding() {
+        let script = parse_script("repeat 1000000 {\n  forward 1\n  repeat 0 {\n    down 1\n  }\n}").unwrap();
+        assert_eq!(script.displacement(), at(1000000, 0));
+
+        let nothing = parse_script("macro idle {\n}\nrepeat 18446744073709551615 {\n  idle\n}").unwrap();
+        assert_eq!(nothing.moves().count(), 0);
+    }
+
+    #[test]
+    fn macros_that_do_nothing_are_skipped_straight_away() {
+        // a64 calls a63 twice, which calls a62 twice and so on
+        let mut source = String::from("macro a0 {\n}\n");
+        for i in 1..=64 {
+            source += &format!("macro a{} {{\n  a{}\n  a{}\n}}\n", i, i - 1, i - 1);
+        }
+        source += "a64\nforward 1";
+
+        assert_eq!(displacements(&source), (at(1, 0), at(1, 0)));
+    }
+
+    #[test]
+    fn too_many_moves_have_no_displacement() {
+        assert_eq!(displacements("repeat 18446744073709551615 {\n  forward 1\n}"), (None, None));
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let expected = |text: &str| text.to_string();
+
+        assert_eq!(error_at("forward 1\n\n  fly 3"), (3, 3, expected("fly"), expected(COMMAND)));
+        assert_eq!(error_at("# start\nrepeat x {"), (2, 8, expected("x"), expected("a number of times")));
+        assert_eq!(error_at("repeat 2 {\n  up 1\n"), (1, 1, expected("repeat 2 {"), expected(
+            "a } to close this before the end of the script"
+        )));
+        assert_eq!(error_at("up 1\n}"), (2, 1, expected("}"), expected("a command, there's no block to close")));
+        assert_eq!(error_at("macro a {\n  a\n}").0, 2);
+        assert_eq!(error_at("macro a {\n}\nmacro a {\n}").1, 7);
+        assert_eq!(error_at("repeat 1 {\n  macro b {\n  }\n}").0, 2);
+        assert_eq!(error_at("macro up {\n}").2, "up");
+        assert_eq!(error_at("forward 1 {").3, "the end of the line");
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Write;
+
+use crate::{Direction, Move};
+
+// Where the submarine is after every move, rather than only where it ends up
+
+// The two ways the submarine can understand its moves, one for each part
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Model {
+    // Part 1 - up and down change the depth directly
+    Plain,
+    // Part 2 - up and down angle the submarine, and it only changes depth
+    // going forward or back
+    Aim,
+}
+
+impl Model {
+    pub fn from_name(name: &str) -> Option<Model> {
+        match name {
+            "plain" => Some(Model::Plain),
+            "aim" => Some(Model::Aim),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Model::Plain => "plain",
+            Model::Aim => "aim",
+        }
+    }
+
+    // Where the submarine is after one more move. The depth grows with the
+    // aim times the distance, which can overflow even an i64, in which case
+    // there's nowhere to go. Going back undoes going forward, depth and all.
+    pub fn step(self, position: Position, next_move: &Move) -> Option<Position> {
+        let distance = next_move.distance as i64;
+        let Position { mut horizontal, mut depth, mut aim } = position;
+
+        match (self, next_move.direction) {
+            (_, Direction::Forward) => horizontal = horizontal.checked_add(distance)?,
+            (_, Direction::Back) => horizontal = horizontal.checked_sub(distance)?,
+            (Model::Plain, Direction::Down) => depth = depth.checked_add(distance)?,
+            (Model::Plain, Direction::Up) => depth = depth.checked_sub(distance)?,
+            (Model::Aim, Direction::Down) => aim = aim.checked_add(distance)?,
+            (Model::Aim, Direction::Up) => aim = aim.checked_sub(distance)?,
+        }
+
+        if self == Model::Aim {
+            match next_move.direction {
+                Direction::Forward => depth = depth.checked_add(aim.checked_mul(distance)?)?,
+                Direction::Back => depth = depth.checked_sub(aim.checked_mul(distance)?)?,
+                Direction::Up | Direction::Down => {},
+            }
+        }
+
+        Some(Position { horizontal, depth, aim })
+    }
+
+    // e.g. "at 15, depth 60, aim 10"
+    pub fn describe(self, position: &Position) -> String {
+        match self {
+            Model::Plain => format!("at {}, depth {}", position.horizontal, position.depth),
+            Model::Aim => format!("at {}, depth {}, aim {}", position.horizontal, position.depth, position.aim),
+        }
+    }
+}
+
+// The aim stays at 0 in the plain model
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+// The most moves a trajectory keeps, or a script is run for. Scripts are run
+// lazily, so a repeat can make far more moves than there's memory to keep the
+// positions of, or time to make them in.
+pub const MAX_MOVES: usize = 1_000_000;
+
+// Why a trajectory ended before the moves did
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    // The next position couldn't be worked out. The move that couldn't be
+    // made isn't in the trajectory.
+    Overflow,
+    // There were more than MAX_MOVES moves
+    TooManyMoves,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trajectory {
+    pub model: Model,
+    pub moves: Vec::<Move>,
+    // Where the submarine starts, then where it is after each of the moves,
+    // so positions[n] is after n moves
+    pub positions: Vec::<Position>,
+    pub stopped: Option<Stop>,
+}
+
+impl Trajectory {
+    // Every position is kept, up to MAX_MOVES of them
+    pub fn new<'a>(model: Model, moves: impl IntoIterator<Item = &'a Move>) -> Trajectory {
+        Trajectory::with_limit(model, moves, MAX_MOVES)
+    }
+
+    pub fn with_limit<'a>(model: Model, moves: impl IntoIterator<Item = &'a Move>, limit: usize) -> Trajectory {
+        let mut trajectory = Trajectory {
+            model,
+            moves: Vec::new(),
+            positions: vec![Position::default()],
+            stopped: None,
+        };
+
+        for next_move in moves {
+            if trajectory.moves.len() == limit {
+                trajectory.stopped = Some(Stop::TooManyMoves);
+                break
+            }
+
+            match model.step(trajectory.last(), next_move) {
+                Some(position) => {
+                    trajectory.moves.push(next_move.clone());
+                    trajectory.positions.push(position);
+                },
+                None => {
+                    trajectory.stopped = Some(Stop::Overflow);
+                    break
+                },
+            }
+        }
+
+        trajectory
+    }
+
+    pub fn last(&self) -> Position {
+        self.positions[self.positions.len() - 1]
+    }
+
+    // Including the start, so it's never less than 0
+    pub fn max_depth(&self) -> i64 {
+        self.positions.iter().map(|position| position.depth).max().unwrap_or(0)
+    }
+
+    // How many moves it takes to get deeper than depth, if the submarine
+    // ever does
+    pub fn first_deeper_than(&self, depth: i64) -> Option<usize> {
+        self.positions.iter().position(|position| position.depth > depth)
+    }
+
+    // How many moves it takes to come up out of the water, if it ever does
+    pub fn first_surfacing(&self) -> Option<usize> {
+        self.positions.iter().position(|position| position.depth < 0)
+    }
+
+    pub fn surfaces(&self) -> bool {
+        self.first_surfacing().is_some()
+    }
+
+    // A row for the start and then each of the moves, with the aim only for
+    // the model that has one
+    pub fn to_csv(&self) -> String {
+        let aim = self.model == Model::Aim;
+        let mut csv = String::from("move,command,horizontal,depth");
+        csv.push_str(if aim { ",aim\n" } else { "\n" });
+
+        for (i, position) in self.positions.iter().enumerate() {
+            let command = match i {
+                0 => String::new(),
+                _ => format!("{} {}", direction_name(self.moves[i - 1].direction), self.moves[i - 1].distance),
+            };
+
+            write!(csv, "{},{},{},{}", i, command, position.horizontal, position.depth).unwrap();
+            if aim {
+                write!(csv, ",{}", position.aim).unwrap();
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    // The path as a line, squashed or stretched to fit, with the surface
+    // drawn across at depth 0. Depth goes down the picture as it does in the
+    // sea. The aim model's depths tend to be far bigger than its horizontal
+    // distances, so the two are scaled separately.
+    pub fn to_svg(&self) -> String {
+        const WIDTH: f64 = 800.0;
+        const HEIGHT: f64 = 400.0;
+        const MARGIN: f64 = 10.0;
+
+        let range = |values: Vec::<i64>| {
+            let (min, max) = (values.iter().min().unwrap(), values.iter().max().unwrap());
+            (*min as f64, (*max as f64 - *min as f64).max(1.0))
+        };
+        let (left, across) = range(self.positions.iter().map(|position| position.horizontal).collect());
+        let (top, down) = range(self.positions.iter().map(|position| position.depth).collect());
+
+        let x = |horizontal: i64| MARGIN + (horizontal as f64 - left) / across * (WIDTH - 2.0 * MARGIN);
+        let y = |depth: i64| MARGIN + (depth as f64 - top) / down * (HEIGHT - 2.0 * MARGIN);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n\
+             <rect width=\"{}\" height=\"{}\" fill=\"#000000\"/>\n\
+             <line x1=\"0\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#3c78c8\"/>\n",
+            WIDTH, HEIGHT, WIDTH, HEIGHT, WIDTH, HEIGHT, y(0), WIDTH, y(0)
+        );
+
+        let points: Vec::<String> = self
+            .positions
+            .iter()
+            .map(|position| format!("{:.1},{:.1}", x(position.horizontal), y(position.depth)))
+            .collect();
+
+        writeln!(svg, "<polyline points=\"{}\" fill=\"none\" stroke=\"#ffdc00\"/>", points.join(" ")).unwrap();
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Forward => "forward",
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Back => "back",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_aim_displacement, get_displacement, parse_script, Day02};
+    use aoc::{property, Solution};
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+
+    fn trajectory(model: Model, source: &str) -> Trajectory {
+        Trajectory::new(model, parse_script(source).unwrap().moves())
+    }
+
+    #[test]
+    fn example_trajectories() {
+        let plain = trajectory(Model::Plain, TEST_INPUT);
+        let aim = trajectory(Model::Aim, TEST_INPUT);
+
+        assert_eq!(plain.positions.len(), 7);
+        assert_eq!((plain.max_depth(), plain.first_deeper_than(5), plain.surfaces()), (10, Some(5), false));
+        assert_eq!((aim.max_depth(), aim.first_deeper_than(0), aim.first_deeper_than(60)), (60, Some(3), None));
+        assert_eq!(aim.last(), Position { horizontal: 15, depth: 60, aim: 10 });
+    }
+
+    #[test]
+    fn surfacing_is_found() {
+        assert_eq!(trajectory(Model::Plain, "up 3\nforward 1").first_surfacing(), Some(1));
+        assert_eq!(trajectory(Model::Aim, "up 3\nforward 1").first_surfacing(), Some(2));
+        assert_eq!(trajectory(Model::Aim, "down 3\nforward 1\nback 1").first_surfacing(), None);
+    }
+
+    #[test]
+    fn trajectories_stop_where_they_overflow() {
+        let course = ["down 2147483647", "forward 2147483647"].repeat(3).join("\n");
+        let trajectory = trajectory(Model::Aim, &course);
+
+        assert_eq!(trajectory.stopped, Some(Stop::Overflow));
+        assert_eq!(trajectory.moves.len(), trajectory.positions.len() - 1);
+        assert_eq!(trajectory.moves.len(), 3);
+    }
+
+    #[test]
+    fn endless_scripts_stop_at_the_limit() {
+        let script = parse_script("repeat 18446744073709551615 {\n  forward 1\n}").unwrap();
+        let trajectory = Trajectory::with_limit(Model::Plain, script.moves(), 1000);
+
+        assert_eq!(trajectory.stopped, Some(Stop::TooManyMoves));
+        assert_eq!(trajectory.last().horizontal, 1000);
+
+        let exact = Trajectory::with_limit(Model::Plain, &Day02::parse("up 1\ndown 1").unwrap(), 2);
+        assert_eq!(exact.stopped, None);
+    }
+
+    #[test]
+    fn trajectories_end_up_at_the_displacement() {
+        let directions = ["forward", "up", "down", "back"];
+        let moves = property::vecs(property::pairs(property::numbers(0..=3), property::numbers(0..=20)), 0..=20);
+
+        property::check(2000, &moves, |moves| {
+            let source: Vec::<String> =
+                moves.iter().map(|(d, distance)| format!("{} {}", directions[*d as usize], distance)).collect();
+            let script = parse_script(&source.join("\n")).unwrap();
+
+            let plain = Trajectory::new(Model::Plain, script.moves()).last();
+            let aim = Trajectory::new(Model::Aim, script.moves()).last();
+            let plain_displacement = get_displacement(script.moves()).unwrap();
+            let aim_displacement = get_aim_displacement(script.moves()).unwrap();
+
+            (plain.horizontal, plain.depth) == (plain_displacement.horizontal, plain_displacement.vertical)
+                && (aim.horizontal, aim.depth) == (aim_displacement.horizontal, aim_displacement.vertical)
+        });
+    }
+
+    #[test]
+    fn csv_has_a_row_for_each_position() {
+        let moves = Day02::parse("down 2\nforward 3").unwrap();
+
+        assert_eq!(
+            Trajectory::new(Model::Plain, &moves).to_csv(),
+            "move,command,horizontal,depth\n0,,0,0\n1,down 2,0,2\n2,forward 3,3,2\n"
+        );
+        assert_eq!(
+            Trajectory::new(Model::Aim, &moves).to_csv(),
+            "move,command,horizontal,depth,aim\n0,,0,0,0\n1,down 2,0,0,2\n2,forward 3,3,6,2\n"
+        );
+    }
+
+    #[test]
+    fn svg_draws_the_path_and_the_surface() {
+        let svg = trajectory(Model::Plain, "down 2\nforward 3\nup 4").to_svg();
+
+        assert!(svg.contains("<polyline points=\"10.0,200.0 10.0,390.0 790.0,390.0 790.0,10.0\""));
+        assert!(svg.contains("<line x1=\"0\" y1=\"200.0\""));
+    }
+}
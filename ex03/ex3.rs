@@ -1,30 +1,62 @@
-use std::fs;
-use std::io::Error;
+use aoc::{Answer, Details, ParseError, Solution};
 
-const FILEPATH: &str = "input.txt";
-const BIT_LENGTH: usize = 12;
-const FILE_LENGTH: u32 = 1000;
+mod generate;
 
-fn generate_input_vec() -> Result<Vec::<u32>, Error> {
-    let file_contents = fs::read_to_string(FILEPATH)?;
+// The diagnostic numbers are held in a u32, and we need to be able to multiply
+// the gamma and epsilon values together in an isize
+const MAX_BIT_LENGTH: usize = 31;
 
-    let input_vec: Vec::<u32> = file_contents
-        .split("\n")
-        .map(|x| isize::from_str_radix(x, 2).unwrap() as u32)
-        .collect();
+// The diagnostic report, along with how many bits long each of its numbers is
+#[derive(Debug)]
+pub struct Diagnostics {
+    bit_length: usize,
+    values: Vec::<u32>,
+}
+
+fn generate_input_vec(input: &str) -> Result<Diagnostics, ParseError> {
+    // Every number is as long as the first one, and there has to be one for
+    // there to be any bits to count
+    let (_, first) = aoc::input::lines(input)
+        .next()
+        .ok_or_else(|| ParseError::new(0, input, input, "a binary number"))?;
+
+    let bit_length = first.len();
+    let expected = format!("a binary number of {} bits", bit_length);
+
+    let values = aoc::input::lines(input)
+        .map(|(i, x)| {
+            // Check the digits ourselves so that we can point at the bad one,
+            // from_str_radix would also let a leading + through
+            if let Some((pos, c)) = x.char_indices().find(|(_, c)| *c != '0' && *c != '1') {
+                return Err(ParseError::new(i, x, &x[pos..pos + c.len_utf8()], &expected))
+            }
+
+            if x.is_empty() || x.len() != bit_length {
+                return Err(ParseError::new(i, x, x, &expected))
+            }
 
-    Ok(input_vec)
+            if x.len() > MAX_BIT_LENGTH {
+                let expected = format!("a binary number of at most {} bits", MAX_BIT_LENGTH);
+                return Err(ParseError::new(i, x, x, &expected))
+            }
+
+            // we've checked every digit above so this can't fail
+            Ok(u32::from_str_radix(x, 2).unwrap())
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Diagnostics { bit_length, values })
 }
 
-fn count_bit_columns(vec: &[u32]) -> [u32; 12] {
-    let mut final_array: [u32; BIT_LENGTH] = [0; BIT_LENGTH];
+fn count_bit_columns(vec: &[u32], bit_length: usize) -> Vec::<u32> {
+    let mut final_array: Vec::<u32> = vec![0; bit_length];
     
     // there are probably nicer ways to do this character by character, but i guess this
     // is O(N) because we just scan through once which is okay
     for line in vec {
-        for i in 0..BIT_LENGTH {
-            if line & (1 << (BIT_LENGTH - i - 1)) != 0 {
-                final_array[i] += 1;
+        for (i, count) in final_array.iter_mut().enumerate() {
+            if line & (1 << (bit_length - i - 1)) != 0 {
+                *count += 1;
             }
         }
     }
@@ -34,12 +66,13 @@ fn count_bit_columns(vec: &[u32]) -> [u32; 12] {
 
 // Building a new bit string seems a bit shit here I imagine there are nicer ways to do
 // both steps to part 1 than this
-fn generate_gamma_epsilon_vals(final_array: [u32; 12]) -> (isize, isize) {
+fn generate_gamma_epsilon_vals(final_array: &[u32], file_length: usize) -> (isize, isize) {
     let mut gamma_binary = String::new();
     let mut epsilon_binary = String::new();
 
     for val in final_array.iter() {
-        if *val > FILE_LENGTH / 2 {
+        // a one is the most common bit if more than half of the lines have it
+        if 2 * (*val as usize) > file_length {
             gamma_binary.push('1');
             epsilon_binary.push('0');
         } else {
@@ -51,33 +84,30 @@ fn generate_gamma_epsilon_vals(final_array: [u32; 12]) -> (isize, isize) {
     let gamma_val = isize::from_str_radix(&gamma_binary[..], 2).unwrap();
     let epsilon_val = isize::from_str_radix(&epsilon_binary[..], 2).unwrap();
 
-    println!("Gamma value = {}", gamma_val);
-    println!("Epsilon value = {}", epsilon_val);
-
     (gamma_val, epsilon_val)
 }
 
 // helper methods which return a vec of the values with zero/one at a given
 // bit index
-fn get_zero_bit_vec(vec: &Vec::<u32>, bit_index: usize) -> Vec::<u32> {
-    vec.clone()
-        .into_iter()
-        .filter(|x| *x & 1 << (BIT_LENGTH - bit_index - 1) == 0)
+fn get_zero_bit_vec(vec: &[u32], bit_index: usize, bit_length: usize) -> Vec::<u32> {
+    vec.iter()
+        .copied()
+        .filter(|x| *x & 1 << (bit_length - bit_index - 1) == 0)
         .collect()
 }
 
-fn get_one_bit_vec(vec: &Vec::<u32>, bit_index: usize) -> Vec::<u32> {
-    vec.clone()
-        .into_iter()
-        .filter(|x| *x & 1 << (BIT_LENGTH - bit_index - 1) != 0)
+fn get_one_bit_vec(vec: &[u32], bit_index: usize, bit_length: usize) -> Vec::<u32> {
+    vec.iter()
+        .copied()
+        .filter(|x| *x & 1 << (bit_length - bit_index - 1) != 0)
         .collect()
 }
 
 
-fn get_oxygen_generator_rating(input_vec: &mut Vec::<u32>) -> Option<u32> {
-    for i in 0..BIT_LENGTH {
-        let zeros_vec: Vec::<u32> = get_zero_bit_vec(input_vec, i);
-        let ones_vec = get_one_bit_vec(input_vec, i);
+fn get_oxygen_generator_rating(input_vec: &mut Vec::<u32>, bit_length: usize) -> Option<u32> {
+    for i in 0..bit_length {
+        let zeros_vec: Vec::<u32> = get_zero_bit_vec(input_vec, i, bit_length);
+        let ones_vec = get_one_bit_vec(input_vec, i, bit_length);
 
         if ones_vec.len() >= zeros_vec.len() {
             *input_vec = ones_vec;
@@ -85,22 +115,23 @@ fn get_oxygen_generator_rating(input_vec: &mut Vec::<u32>) -> Option<u32> {
             *input_vec = zeros_vec;
         }
 
+        aoc::trace::event(|| format!("oxygen: {} numbers left after bit {}", input_vec.len(), i));
+
         if input_vec.len() == 1 {
-            println!("Oxygen Generator Rating = {}", input_vec[0]);
             return Some(input_vec[0])
         }
     }
 
-    println!("We haven't taken enough measurements to determine the Oxygen Generator Rating");
+    // We haven't taken enough measurements to determine the Oxygen Generator Rating
     None
 }
 
 // This is basically a copy paste of the function above.
 // I could probably split these two out into a "most" and "least" common bit search
-fn get_co2_scubber_rating(input_vec: &mut Vec::<u32>) -> Option<u32> {
-    for i in 0..BIT_LENGTH {
-        let zeros_vec: Vec::<u32> = get_zero_bit_vec(input_vec, i);
-        let ones_vec = get_one_bit_vec(input_vec, i);
+fn get_co2_scubber_rating(input_vec: &mut Vec::<u32>, bit_length: usize) -> Option<u32> {
+    for i in 0..bit_length {
+        let zeros_vec: Vec::<u32> = get_zero_bit_vec(input_vec, i, bit_length);
+        let ones_vec = get_one_bit_vec(input_vec, i, bit_length);
 
         if zeros_vec.len() <= ones_vec.len() {
             *input_vec = zeros_vec;
@@ -108,33 +139,123 @@ fn get_co2_scubber_rating(input_vec: &mut Vec::<u32>) -> Option<u32> {
             *input_vec = ones_vec;
         }
 
+        aoc::trace::event(|| format!("CO2: {} numbers left after bit {}", input_vec.len(), i));
+
         if input_vec.len() == 1 {
-            println!("CO2 Scrubber Rating {}", input_vec[0]);
             return Some(input_vec[0])
         }
     }
 
-    println!("We haven't taken enough measurements to determine the CO2 Scrubber Rating");
+    // We haven't taken enough measurements to determine the CO2 Scrubber Rating
     None
 }
 
-fn main() -> Result<(), Error> {
-    let input_vec = generate_input_vec()?;
+fn gamma_epsilon_vals(diagnostics: &Diagnostics) -> (isize, isize) {
+    let bit_column_count = count_bit_columns(&diagnostics.values, diagnostics.bit_length);
 
-    println!("Part 1!");
+    generate_gamma_epsilon_vals(&bit_column_count, diagnostics.values.len())
+}
+
+fn life_support_ratings(diagnostics: &Diagnostics) -> (Option<u32>, Option<u32>) {
+    let bit_length = diagnostics.bit_length;
+
+    (
+        get_oxygen_generator_rating(&mut diagnostics.values.clone(), bit_length),
+        get_co2_scubber_rating(&mut diagnostics.values.clone(), bit_length),
+    )
+}
 
-    let bit_column_count = count_bit_columns(&input_vec);
-    let (gamma_val, epsilon_val) = generate_gamma_epsilon_vals(bit_column_count);
-    println!("Power consumption of submarine = {}", gamma_val * epsilon_val);
+pub struct Day03;
 
-    println!("\nPart 2!");
+impl Solution for Day03 {
+    type Input = Diagnostics;
 
-    if let (Some(oxygen_rating), Some(co2_rating)) = (
-        get_oxygen_generator_rating(&mut input_vec.clone()),
-        get_co2_scubber_rating(&mut input_vec.clone())
-    ) {
-        println!("The life support rating is {}", oxygen_rating * co2_rating);
+    fn parse(input: &str) -> Result<Diagnostics, ParseError> {
+        generate_input_vec(input)
     }
-    
-    Ok(())
-}
\ No newline at end of file
+
+    // Power consumption of the submarine
+    fn part1(diagnostics: &Diagnostics) -> Option<Answer> {
+        let (gamma_val, epsilon_val) = gamma_epsilon_vals(diagnostics);
+
+        Some((gamma_val * epsilon_val).into())
+    }
+
+    // Life support rating of the submarine
+    fn part2(diagnostics: &Diagnostics) -> Option<Answer> {
+        let (oxygen_rating, co2_rating) = life_support_ratings(diagnostics);
+
+        Some((oxygen_rating? as u64 * co2_rating? as u64).into())
+    }
+
+    fn part1_details(diagnostics: &Diagnostics) -> Details {
+        let (gamma_val, epsilon_val) = gamma_epsilon_vals(diagnostics);
+
+        vec![
+            ("gamma", gamma_val.into()),
+            ("epsilon", epsilon_val.into()),
+        ]
+    }
+
+    // Either rating can be missing, so we only show the ones we found
+    fn part2_details(diagnostics: &Diagnostics) -> Details {
+        let (oxygen_rating, co2_rating) = life_support_ratings(diagnostics);
+
+        [("oxygen_generator_rating", oxygen_rating), ("co2_scrubber_rating", co2_rating)]
+            .into_iter()
+            .filter_map(|(name, rating)| Some((name, rating?.into())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day03>(TEST_INPUT, 198, 230);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day03>(&input, 198, 230);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day03>(INPUT, 738234, 3969126);
+    }
+
+    #[test]
+    fn bad_bit_is_reported() {
+        let error = Day03::parse("00100\n11210").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "2"));
+    }
+
+    #[test]
+    fn numbers_must_all_be_the_same_length() {
+        let error = Day03::parse("00100\n1110").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day03>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day03>(seed, &GenOptions::default());
+        }
+
+        let long = GenOptions { bits: Some(30), ..GenOptions::default() };
+        aoc::assert_generated::<Day03>(0, &long);
+    }
+}
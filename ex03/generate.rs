@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day03;
+
+// How many reports we try before giving up on finding one where both life
+// support ratings can be found
+const MAX_ATTEMPTS: usize = 100;
+
+// A diagnostic report of distinct random numbers. The life support filters
+// can still throw every number away (when all of the remaining numbers share
+// a bit, the least common bit is the one none of them have), so we keep
+// trying until we get a report where they don't.
+impl Generator for Day03 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let bits = options.bits.unwrap_or(12).clamp(1, 31);
+        let size = options.size.unwrap_or(1000).clamp(1, 1 << bits);
+
+        let mut numbers = random_report(rng, bits, size);
+        let mut ratings = life_support_ratings(&numbers, bits);
+
+        for _ in 1..MAX_ATTEMPTS {
+            if ratings.is_some() {
+                break
+            }
+
+            numbers = random_report(rng, bits, size);
+            ratings = life_support_ratings(&numbers, bits);
+        }
+
+        let (gamma, epsilon) = power_rates(&numbers, bits);
+
+        Generated {
+            input: numbers.join("\n"),
+            part1: Some((gamma * epsilon).into()),
+            part2: ratings.map(|(oxygen, co2)| (oxygen * co2).into()),
+        }
+    }
+}
+
+fn random_report(rng: &mut Rng, bits: usize, size: usize) -> Vec::<String> {
+    let mut seen = HashSet::<u64>::new();
+    let mut numbers = Vec::<String>::new();
+
+    while numbers.len() < size {
+        let number = rng.range(0..1 << bits);
+
+        if seen.insert(number) {
+            numbers.push(format!("{:0width$b}", number, width = bits));
+        }
+    }
+
+    numbers
+}
+
+fn life_support_ratings(numbers: &[String], bits: usize) -> Option<(u64, u64)> {
+    Some((filter_by_bits(numbers, bits, true)?, filter_by_bits(numbers, bits, false)?))
+}
+
+// Counts the ones in each column of the binary strings
+fn ones_in_column(numbers: &[&String], column: usize) -> usize {
+    numbers.iter().filter(|n| n.as_bytes()[column] == b'1').count()
+}
+
+fn power_rates(numbers: &[String], bits: usize) -> (u64, u64) {
+    let numbers: Vec::<&String> = numbers.iter().collect();
+    let gamma: String = (0..bits)
+        .map(|column| if 2 * ones_in_column(&numbers, column) > numbers.len() { '1' } else { '0' })
+        .collect();
+
+    let gamma = u64::from_str_radix(&gamma, 2).unwrap();
+    let epsilon = !gamma & ((1 << bits) - 1);
+
+    (gamma, epsilon)
+}
+
+// Keeps the numbers with the most (or least) common bit in each column until
+// there's only one left, with ties going to 1 for the most common and 0 for
+// the least. Gives None if there isn't exactly one left at the end.
+fn filter_by_bits(numbers: &[String], bits: usize, most_common: bool) -> Option<u64> {
+    let mut remaining: Vec::<&String> = numbers.iter().collect();
+
+    for column in 0..bits {
+        if remaining.len() == 1 {
+            break
+        }
+
+        let ones = ones_in_column(&remaining, column);
+        let zeros = remaining.len() - ones;
+        let keep = if (ones >= zeros) == most_common { b'1' } else { b'0' };
+
+        remaining.retain(|n| n.as_bytes()[column] == keep);
+    }
+
+    match remaining[..] {
+        [number] => Some(u64::from_str_radix(number, 2).unwrap()),
+        _ => None,
+    }
+}
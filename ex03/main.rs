@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex03::Day03;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day03>(FILEPATH)
+}
@@ -1,7 +1,8 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::iter;
 
-const FILEPATH: &str = "./input.txt";
+use aoc::{parser, Answer, Details, Frames, ParseError, Solution};
+
+mod generate;
 
 // Diagonals don't count!
 const LINES: [[usize; 5]; 10] = [
@@ -23,27 +24,22 @@ struct CardEntry {
     marked: bool,
 }
 
+#[derive(Clone, Debug)]
 struct Card {
-    index: usize,
     entries: [CardEntry; 25],
     is_finished: bool,
 }
 
 impl Card {
-    pub fn setup(numbers: &[u32], card_number: usize) -> Card {
-        if numbers.len() != 25 {
-            panic!("Parsed input.txt incorrectly and have an incorrectly sized card. Aborting!");
-        }
-
+    pub fn setup(numbers: &[u32; 25]) -> Card {
         let mut entries: [CardEntry; 25] = [CardEntry {number: 0, marked: false}; 25];
 
-        for (i, num) in numbers.into_iter().enumerate() {
+        for (i, num) in numbers.iter().enumerate() {
             entries[i].number = *num;
         }
 
         Card {
-            index: card_number - 1,
-            entries: entries,
+            entries,
             is_finished: false
         }
     }
@@ -68,6 +64,41 @@ impl Card {
         None
     }
 
+    // Whether an entry is part of a line which has been completely marked
+    fn is_on_complete_line(&self, index: usize) -> bool {
+        LINES
+            .iter()
+            .filter(|line| line.contains(&index))
+            .any(|line| line.iter().all(|i| self.entries[*i].marked))
+    }
+
+    // Draws the card for the terminal, one row per line, with the marked
+    // numbers in green and any winning line picked out
+    fn draw(&self) -> Vec::<String> {
+        self.entries
+            .chunks(5)
+            .enumerate()
+            .map(|(row, entries)| {
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(column, entry)| {
+                        let text = format!("{:>2}", entry.number);
+
+                        if self.is_on_complete_line(row * 5 + column) {
+                            aoc::highlight(&text, [0; 3], [255, 210, 60])
+                        } else if entry.marked {
+                            aoc::paint(&text, [80, 220, 100])
+                        } else {
+                            aoc::paint(&text, [110; 3])
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
     fn check_is_finished(&self) -> bool {
         for line in LINES {
             if self.entries[line[0]].marked &&
@@ -83,111 +114,293 @@ impl Card {
     }
 }
 
-fn read_input_file() -> (Vec::<u32>, Vec::<Card>){
-    let file = File::open(FILEPATH).unwrap();
-    let reader = BufReader::new(file);
+// A card is a block of 5 rows of 5 numbers. The numbers can be padded with
+// extra spaces to line them up, including at the start of a row.
+fn parse_card(block: &[(usize, &str)]) -> Result<Card, ParseError> {
+    if let Some((i, line)) = block.get(5) {
+        return Err(ParseError::new(*i, line, line, "a blank line after 5 rows of a bingo card"))
+    }
 
-    let mut card_numbers: Vec::<u32> = Vec::new();
-    let mut number_of_cards: usize = 0;
+    let numbers = parser::separated(parser::integer::<u32>("a number on a bingo card"), parser::whitespace());
+    let row = parser::try_map(
+        parser::preceded(parser::optional_whitespace(), numbers),
+        "a row of 5 numbers on a bingo card",
+        |row| <[u32; 5]>::try_from(row).ok(),
+    );
 
-    let mut bingo_numbers: Vec::<u32> = Vec::new();
-    let mut bingo_cards = Vec::<Card>::new();
+    let rows = parser::rows(block, row)?;
 
-    for (line_number, line) in reader.lines().enumerate() {
-        // line 0 of the file, collect the bingo numbers
-        if line_number == 0 {
-            bingo_numbers = line
-                .unwrap()
-                .split(",")
-                .map(|x| x.parse().expect("Failed to parse bingo numbers to u32"))
-                .collect();
-        }
-        // line one of the file, we haven't parsed a bingo card just yet
-        else if line_number == 1 {
-            // do nothing
-        }
-        // every sixth line is a blank line where should have finished parsing
-        // a new card
-        else if (line_number - 1) % 6 == 0 {
-            number_of_cards = number_of_cards + 1;
-            bingo_cards.push(Card::setup(&card_numbers, number_of_cards));
-            card_numbers = vec![];
-        }
-        // parse the next line of a bingo card
-        else {
-            let mut new_line: Vec::<u32> = line
-                .unwrap()
-                .split(" ")
-                .filter(|x| x != &"")
-                .map(|x| x.parse().expect("Failed to parse line of bingo card to u32"))
-                .collect();
-
-            card_numbers.append(&mut new_line);
-        }
-    }
+    // A short card is reported where its next row should have been
+    let numbers: [u32; 25] = rows
+        .concat()
+        .try_into()
+        .map_err(|_| ParseError::new(block[block.len() - 1].0 + 1, "", "", "a bingo card of 5 rows"))?;
 
-    // add a final card if we have a valid bingo card
-    if card_numbers.len() == 25 {
-        number_of_cards = number_of_cards + 1;
-        bingo_cards.push(Card::setup(&card_numbers, number_of_cards));
-    }
+    Ok(Card::setup(&numbers))
+}
+
+// The bingo numbers on the first line, then the cards, each after a blank line
+fn read_input_file(input: &str) -> Result<(Vec::<u32>, Vec::<Card>), ParseError> {
+    let blocks = aoc::input::paragraphs(input);
+
+    let Some((numbers_block, cards)) = blocks.split_first() else {
+        return Ok((Vec::new(), Vec::new()))
+    };
 
-    (bingo_numbers, bingo_cards)
+    let numbers = parser::separated(parser::integer("a bingo number"), parser::padded(parser::literal(",")));
+    let bingo_numbers = parser::one_line(numbers_block, numbers, "a blank line")?;
+
+    let bingo_cards = cards
+        .iter()
+        .map(|card| parse_card(card))
+        .collect::<Result<_, _>>()?;
+
+    Ok((bingo_numbers, bingo_cards))
 }
 
-fn calculate_unmarked_sum(card: &Card) -> u32 {
+// A card's numbers can add up to more than a u32 holds
+fn calculate_unmarked_sum(card: &Card) -> u64 {
     let mut unmarked_sum = 0;
     for entry in card.entries {
         if !entry.marked {
-            unmarked_sum = unmarked_sum + entry.number;
+            unmarked_sum += entry.number as u64;
         }
     }
 
     unmarked_sum
 }
 
-fn play_bingo_game(bingo_numbers: Vec::<u32>, bingo_cards: &mut Vec::<Card>) {
-    let mut unfinished_indices: Vec::<u32> = (0..100).collect();
+// A card finishing during the game, recorded so that we can work out the
+// score of the first and last cards to win
+struct Win {
+    card_index: usize,
+    // Counting the first number drawn as turn 1, as the animation does
+    turn: usize,
+    final_number: u32,
+    unmarked_sum: u64,
+}
+
+impl Win {
+    fn score(&self) -> u128 {
+        self.final_number as u128 * self.unmarked_sum as u128
+    }
 
-    for (i, number) in bingo_numbers.iter().enumerate() {
-        for card in &mut *bingo_cards {
+    fn details(&self) -> Details {
+        vec![
+            ("card", self.card_index.into()),
+            ("turn", self.turn.into()),
+            ("final_number", self.final_number.into()),
+            ("unmarked_sum", self.unmarked_sum.into()),
+        ]
+    }
+}
+
+// Plays the whole game, returning every card's win in the order they finished
+fn play_bingo_game(bingo_numbers: &[u32], bingo_cards: &mut [Card]) -> Vec::<Win> {
+    let mut wins = Vec::<Win>::new();
+
+    for (turn, number) in (1..).zip(bingo_numbers) {
+        aoc::trace::event(|| format!("turn {}: drew {}", turn, number));
+
+        for (card_index, card) in bingo_cards.iter_mut().enumerate() {
             if card.is_finished {
                 continue; // we don't want to update the card again
             }
 
             card.update(*number);
             if card.is_finished {
-                // first card has won
-                if unfinished_indices.len() == 100 {
-                    print_output(&card, i, *number, true);
-                }
-
-                // last card has won
-                if unfinished_indices.len() == 1 {
-                    print_output(&card, i, *number, false);
-                }
-
-                // remove the finished index from the unfinished indices list
-                unfinished_indices.retain(|x| *x != card.index as u32);
+                aoc::trace::event(|| format!("card {} wins ({} so far)", card_index, wins.len() + 1));
+                wins.push(Win {
+                    card_index,
+                    turn,
+                    final_number: *number,
+                    unmarked_sum: calculate_unmarked_sum(card),
+                });
             }
         }
     }
+
+    wins
+}
+
+#[derive(Debug)]
+pub struct Bingo {
+    numbers: Vec::<u32>,
+    cards: Vec::<Card>,
+}
+
+impl Bingo {
+    // Plays a game on a fresh copy of the cards
+    fn play(&self) -> Vec::<Win> {
+        play_bingo_game(&self.numbers, &mut self.cards.clone())
+    }
+}
+
+// How many cards are drawn side by side in each frame
+const CARDS_PER_ROW: usize = 5;
+
+// Draws one frame of the game, with the cards laid out in a grid
+fn draw_game(cards: &[Card], header: &str) -> String {
+    let mut frame = format!("{}\n", header);
+
+    for row_of_cards in cards.chunks(CARDS_PER_ROW) {
+        frame.push('\n');
+
+        let drawn: Vec::<Vec::<String>> = row_of_cards.iter().map(Card::draw).collect();
+        for line in 0..5 {
+            let line: Vec::<&str> = drawn.iter().map(|card| card[line].as_str()).collect();
+            frame.push_str(&line.join("    "));
+            frame.push('\n');
+        }
+    }
+
+    frame
+}
+
+// Plays the game a number at a time, drawing the cards after every turn until
+// the last card has won
+fn animate_game(bingo: &Bingo) -> Frames {
+    let mut cards = bingo.cards.clone();
+    let start = draw_game(&cards, "Turn 0");
+    let mut all_won = false;
+
+    let turns = (1..).zip(bingo.numbers.clone()).map_while(move |(turn, number)| {
+        if all_won {
+            return None
+        }
+
+        for card in cards.iter_mut().filter(|card| !card.is_finished) {
+            card.update(number);
+        }
+
+        let finished = cards.iter().filter(|card| card.is_finished).count();
+        all_won = finished == cards.len();
+
+        let header = format!("Turn {}: drew {}, {} of {} cards have won", turn, number, finished, cards.len());
+        Some(draw_game(&cards, &header))
+    });
+
+    Box::new(iter::once(start).chain(turns))
 }
 
-fn print_output(card: &Card, turn: usize, final_number: u32, is_first: bool) {
-    if is_first {
-        println!("Card {} has finished first after turn {}!", card.index, turn);
-    } else {
-        println!("Card {} has finished last after turn {}!", card.index, turn);
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Bingo;
+
+    fn parse(input: &str) -> Result<Bingo, ParseError> {
+        let (numbers, cards) = read_input_file(input)?;
+        Ok(Bingo { numbers, cards })
+    }
+
+    // Score of the first card to win
+    fn part1(bingo: &Bingo) -> Option<Answer> {
+        bingo.play().first().map(|win| win.score().into())
+    }
+
+    // Score of the last card to win
+    fn part2(bingo: &Bingo) -> Option<Answer> {
+        bingo.play().last().map(|win| win.score().into())
     }
 
-    let unmarked_sum = calculate_unmarked_sum(&card);
-    println!("Final number = {}", final_number);
-    println!("Unmarked sum = {}", unmarked_sum);
-    println!("Multiplied, gives {}\n", final_number * unmarked_sum);
+    fn part1_details(bingo: &Bingo) -> Details {
+        bingo.play().first().map_or_else(Vec::new, Win::details)
+    }
+
+    fn part2_details(bingo: &Bingo) -> Details {
+        bingo.play().last().map_or_else(Vec::new, Win::details)
+    }
+
+    fn frames(bingo: &Bingo) -> Frames {
+        animate_game(bingo)
+    }
 }
 
-fn main() {
-    let (bingo_numbers, mut bingo_cards) = read_input_file();
-    play_bingo_game(bingo_numbers, &mut bingo_cards);
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day04>(TEST_INPUT, 4512, 1924);
+    }
+
+    #[test]
+    fn drawn_numbers_and_wins_are_traced() {
+        let bingo = Day04::parse(TEST_INPUT).unwrap();
+        let (_, lines) = aoc::trace::capture(aoc::trace::EVENTS, || Day04::part1(&bingo));
+
+        assert_eq!(lines[0], "turn 1: drew 7");
+        assert!(lines.contains(&String::from("card 2 wins (1 so far)")), "{:?}", lines);
+
+        // 24 is the 12th number drawn
+        let details = Day04::part1_details(&bingo);
+        assert!(details.contains(&("turn", 12usize.into())), "{:?}", details);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day04>(&input, 4512, 1924);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day04>(INPUT, 39902, 26936);
+    }
+
+    #[test]
+    fn frames_stop_when_the_last_card_wins() {
+        let bingo = Day04::parse(TEST_INPUT).unwrap();
+        let frames: Vec::<String> = Day04::frames(&bingo).collect();
+
+        // The last card wins on the 15th number, 13
+        assert_eq!(frames.len(), 16);
+        assert!(aoc::strip_ansi(&frames[15]).starts_with("Turn 15: drew 13, 3 of 3 cards have won\n"));
+
+        // The first card to win has its top row picked out
+        let winning_row = aoc::highlight("14", [0; 3], [255, 210, 60]);
+        assert!(frames[12].contains(&winning_row));
+        assert!(!frames[11].contains(&winning_row));
+    }
+
+    #[test]
+    fn short_card_row_is_reported() {
+        let input = "7,4,9\n\n22 13 17 11  0\n 8  2 23  4";
+        let error = Day04::parse(input).unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (4, " 8  2 23  4"));
+    }
+
+    #[test]
+    fn unfinished_card_is_reported() {
+        let input = "7,4,9\n\n22 13 17 11  0\n 8  2 23  4 24";
+        let error = Day04::parse(input).unwrap_err();
+        assert_eq!(error.line, 5);
+    }
+
+    #[test]
+    fn huge_numbers_dont_overflow_the_score() {
+        // The 19 is still unmarked when the third card wins
+        let input = TEST_INPUT.replacen("10 16 15  9 19", "10 16 15  9 4294967295", 1);
+        aoc::assert_answers::<Day04>(&input, 24 * (188 - 19 + 4294967295_u64), 1924);
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day04>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day04>(seed, &GenOptions::default());
+        }
+
+        let many = GenOptions { size: Some(2000), range: Some(500), ..GenOptions::default() };
+        aoc::assert_generated::<Day04>(0, &many);
+    }
+}
@@ -0,0 +1,82 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day04;
+
+// Every number in the range is drawn exactly once, in a random order, so
+// every card wins eventually. The cards are 25 distinct numbers from the same
+// range.
+impl Generator for Day04 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(100).max(1);
+        let range = options.range.unwrap_or(100).max(25);
+
+        let mut numbers: Vec::<u32> = (0..range as u32).collect();
+        rng.shuffle(&mut numbers);
+
+        let cards: Vec::<Vec::<u32>> = (0..size)
+            .map(|_| {
+                let mut card: Vec::<u32> = (0..range as u32).collect();
+                rng.shuffle(&mut card);
+                card.truncate(25);
+                card
+            })
+            .collect();
+
+        let scores = winning_scores(&numbers, &cards);
+
+        let drawn: Vec::<String> = numbers.iter().map(|n| n.to_string()).collect();
+        let mut input = vec![drawn.join(",")];
+        for card in &cards {
+            input.push(String::new());
+            for row in card.chunks(5) {
+                let row: Vec::<String> = row.iter().map(|n| format!("{:>2}", n)).collect();
+                input.push(row.join(" "));
+            }
+        }
+
+        Generated {
+            input: input.join("\n"),
+            part1: scores.first().map(|score| (*score).into()),
+            part2: scores.last().map(|score| (*score).into()),
+        }
+    }
+}
+
+// The score of every card in the order they win. A line is finished on the
+// turn its last number is drawn, and a card wins on the turn its first line
+// is finished, so we can work that out without playing the game.
+fn winning_scores(numbers: &[u32], cards: &[Vec::<u32>]) -> Vec::<u64> {
+    let mut turn_drawn = vec![usize::MAX; numbers.len()];
+    for (turn, number) in numbers.iter().enumerate() {
+        turn_drawn[*number as usize] = turn;
+    }
+
+    let mut wins: Vec::<(usize, usize)> = cards
+        .iter()
+        .enumerate()
+        .map(|(index, card)| {
+            let finished = |line: [usize; 5]| line.iter().map(|i| turn_drawn[card[*i] as usize]).max().unwrap();
+
+            let rows = (0..5).map(|row| [0, 1, 2, 3, 4].map(|column| row * 5 + column));
+            let columns = (0..5).map(|column| [0, 1, 2, 3, 4].map(|row| row * 5 + column));
+            let turn = rows.chain(columns).map(finished).min().unwrap();
+
+            (turn, index)
+        })
+        .collect();
+
+    // Cards winning on the same turn are checked in order
+    wins.sort();
+
+    wins.into_iter()
+        .map(|(turn, index)| {
+            let unmarked: u64 = cards[index]
+                .iter()
+                .filter(|n| turn_drawn[**n as usize] > turn)
+                .map(|n| *n as u64)
+                .sum();
+
+            unmarked * numbers[turn] as u64
+        })
+        .collect()
+}
@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex04::Day04;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day04>(FILEPATH)
+}
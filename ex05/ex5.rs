@@ -1,30 +1,33 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::HashMap;
 
-const FILEPATH: &str = "./input.txt";
+use aoc::{parser, Answer, Grid, Image, ParseError, Solution};
 
-const MAP_LENGTH: usize = 1000;
+mod generate;
+
+// The biggest coordinate we'll accept from the input
+const MAX_COORDINATE: usize = 9_999;
+
+// The map is sized to fit around the lines we're given, but past this many
+// points only the points the lines cover are kept, so that one stray line
+// across the whole of the sea doesn't need 100MB of map
+const MAX_DENSE_POINTS: usize = 1 << 22;
 
 #[derive(Debug, Clone, Copy)]
-struct LineSegment {
+pub struct LineSegment {
     x1: isize,
     y1: isize,
     x2: isize,
     y2: isize,
 }
 
-enum LineDir {
+pub enum LineDir {
     Forwards,
     Backwards,
     Stationary
 }
 
 impl LineSegment {
-    pub fn create(segment_vec: &[isize]) -> LineSegment {
-        if segment_vec.len() != 4 {
-            panic!("Incorrect use of LineSegment::create");
-        }
-
+    pub fn create(segment_vec: &[isize; 4]) -> LineSegment {
         LineSegment {
             x1: segment_vec[0],
             y1: segment_vec[1],
@@ -69,15 +72,31 @@ impl LineSegment {
     }
 }
 
+// Only whether more than one line covers a point matters, so the counts stop
+// at 255 rather than taking up more memory
+enum Points {
+    // Every point from (left, top) to the bottom right corner of the lines
+    Dense { grid: Grid::<u8>, left: usize, top: usize },
+    Sparse(HashMap<(usize, usize), u8>),
+}
+
 struct Map {
-    points: [[u32; MAP_LENGTH]; MAP_LENGTH]
+    points: Points,
 }
 
 impl Map {
-    fn create() -> Map {
-        Map {
-            points: [[0; MAP_LENGTH]; MAP_LENGTH]
-        }
+    // Big enough for everything from (left, top) to (right, bottom)
+    fn create(left: usize, top: usize, right: usize, bottom: usize) -> Map {
+        let (width, height) = (right + 1 - left, bottom + 1 - top);
+
+        let points = if width * height <= MAX_DENSE_POINTS {
+            Points::Dense { grid: Grid::new(width, height, 0), left, top }
+        } else {
+            aoc::trace::event(|| format!("the map is {}x{}, only keeping the points on lines", width, height));
+            Points::Sparse(HashMap::new())
+        };
+
+        Map { points }
     }
 
     fn add_line(&mut self, segment: LineSegment) {
@@ -88,85 +107,183 @@ impl Map {
         let mut x = segment.x1 as usize;
         let mut y = segment.y1 as usize;
 
-        self.points[x][y] = self.points[x][y] + 1;
+        self.mark_point(x, y);
         for _ in 0..length {
             self.increment_point(&x_dir, &mut x);
             self.increment_point(&y_dir, &mut y);
-            self.points[x][y] = self.points[x][y] + 1;
+            self.mark_point(x, y);
         }
     }
 
+    fn mark_point(&mut self, x: usize, y: usize) {
+        let count = match &mut self.points {
+            Points::Dense { grid, left, top } => &mut grid[(x - *left, y - *top)],
+            Points::Sparse(points) => points.entry((x, y)).or_insert(0),
+        };
+
+        *count = count.saturating_add(1);
+    }
+
     fn increment_point(&self, dir: &LineDir, point: &mut usize) {
         // There must be a better way of doing this
         // It would be nice is `for i in (100..1) { }` worked
         match dir {
-            LineDir::Forwards => *point = *point + 1,
-            LineDir::Backwards => *point = *point - 1,
+            LineDir::Forwards => *point += 1,
+            LineDir::Backwards => *point -= 1,
             LineDir::Stationary => ()
         }
     }
 
-    fn count_mulitple_line_points(&self) -> u32 {
-        let mut total: u32 = 0;
-        for y in 0..MAP_LENGTH {
-            for x in 0..MAP_LENGTH {
-                if self.points[x][y] > 1 {
-                    total = total + 1;
-                }
-            }
+    fn count_mulitple_line_points(&self) -> usize {
+        match &self.points {
+            Points::Dense { grid, .. } => grid.iter().filter(|x| **x > 1).count(),
+            Points::Sparse(points) => points.values().filter(|x| **x > 1).count(),
         }
+    }
+}
 
-        total
+// Draws all of the straight lines onto a map, and the diagonal ones too if
+// asked for
+fn vent_map(segments: &[LineSegment], diagonals: bool) -> Map {
+    let bound = |coordinate: fn(&LineSegment) -> isize, fold: fn(isize, isize) -> isize| {
+        segments.iter().map(coordinate).reduce(fold).unwrap_or(0) as usize
+    };
+    let left = bound(|s| s.x1.min(s.x2), isize::min);
+    let top = bound(|s| s.y1.min(s.y2), isize::min);
+    let right = bound(|s| s.x1.max(s.x2), isize::max);
+    let bottom = bound(|s| s.y1.max(s.y2), isize::max);
+    let mut map = Map::create(left, top, right, bottom);
+
+    for segment in segments {
+        if segment.is_straight() || (diagonals && segment.is_diagonal()) {
+            map.add_line(*segment);
+        } else {
+            aoc::trace::event(|| format!("skipped {:?}", segment));
+        }
     }
+
+    map
+}
+
+fn read_input_file(input: &str) -> Result<Vec::<LineSegment>, ParseError> {
+    // anything outside of the biggest map we'll make can't be drawn
+    let expected = format!("a coordinate between 0 and {}", MAX_COORDINATE);
+    let coordinate = || parser::verify(parser::integer::<isize>(&expected), &expected, |c| {
+        (0..=MAX_COORDINATE as isize).contains(c)
+    });
+
+    // e.g. "0,9 -> 5,9"
+    let point = || parser::separated_pair(coordinate(), parser::literal(","), coordinate());
+    let line = parser::separated_pair(point(), parser::literal(" -> "), point());
+
+    parser::parse_lines(input, parser::map(line, |((x1, y1), (x2, y2))| LineSegment::create(&[x1, y1, x2, y2])))
 }
 
-fn read_input_file() -> Vec::<LineSegment> {
-    let file = File::open(FILEPATH).unwrap();
-    let reader = BufReader::new(file);
+pub struct Day05;
 
-    let mut line_segments: Vec::<LineSegment> = Vec::new();
+impl Solution for Day05 {
+    type Input = Vec::<LineSegment>;
 
-    for line in reader.lines() {
-        let formatted_line_str = &line.unwrap()[..].replace(" -> ", ",");
-        let line_segment_vec: Vec::<isize> = formatted_line_str
-            .split(",")
-            .map(|x| x.parse().expect("Failed to cast &str to u32"))
-            .collect();
+    fn parse(input: &str) -> Result<Vec::<LineSegment>, ParseError> {
+        read_input_file(input)
+    }
 
-        line_segments.push(LineSegment::create(&line_segment_vec));
+    // Number of points covered by multiple straight lines
+    fn part1(segments: &Vec::<LineSegment>) -> Option<Answer> {
+        Some(vent_map(segments, false).count_mulitple_line_points().into())
     }
 
-    line_segments
+    // Number of points covered by multiple straight or diagonal lines
+    fn part2(segments: &Vec::<LineSegment>) -> Option<Answer> {
+        Some(vent_map(segments, true).count_mulitple_line_points().into())
+    }
+
+    // Every vent from part 2, brighter where more of them overlap, cropped to
+    // the lines. A map too big to keep every point of is too big to draw.
+    fn render(segments: &Vec::<LineSegment>, _step: Option<u32>) -> Option<Image> {
+        let Points::Dense { grid, .. } = vent_map(segments, true).points else {
+            return None
+        };
+        let max_count = grid.iter().copied().max().unwrap_or(0);
+
+        Some(Image::from_grid(&grid, |count| {
+            if *count == 0 { [0; 3] } else { aoc::heat(*count as u32, max_count as u32) }
+        }))
+    }
 }
 
-fn main() {
-    let segments = read_input_file();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
 
-    let mut map = Map::create();
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
 
-    println!("Part 1");
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day05>(TEST_INPUT, 5, 12);
+    }
 
-    for segment in &segments {
-        if segment.is_straight() {
-            map.add_line(*segment);
-        }
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day05>(&input, 5, 12);
     }
 
-    println!(
-        "Straight line multiple point count = {}\n",
-        map.count_mulitple_line_points()
-    );
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day05>(INPUT, 6666, 19081);
+    }
 
-    println!("Part 2");
+    #[test]
+    fn overlaps_are_drawn_brightest() {
+        let segments = Day05::parse(TEST_INPUT).unwrap();
+        let image = Day05::render(&segments, None).unwrap();
 
-    for segment in &segments {
-        if segment.is_diagonal() {
-            map.add_line(*segment);
-        }
+        // (4, 4) is crossed by the most lines in the example, and (0, 1) by none
+        assert_eq!(image.pixel(4, 4), Some(aoc::heat(1, 1)));
+        assert_eq!(image.pixel(0, 1), Some([0; 3]));
+    }
+
+    #[test]
+    fn huge_maps_only_keep_the_points_on_lines() {
+        let input = "0,0 -> 9999,9999\n0,9999 -> 9999,0\n5000,0 -> 5000,9999";
+        let segments = Day05::parse(input).unwrap();
+
+        assert!(matches!(vent_map(&segments, true).points, Points::Sparse(_)));
+        assert_eq!(Day05::part1(&segments), Some(0.into()));
+        assert_eq!(Day05::part2(&segments), Some(2.into()));
+        assert!(Day05::render(&segments, None).is_none());
     }
 
-    println!(
-        "Straight and diagonal line multiple point count = {}\n",
-        map.count_mulitple_line_points()
-    );
-}
\ No newline at end of file
+    #[test]
+    fn maps_only_cover_the_lines() {
+        let segments = Day05::parse("9000,9000 -> 9003,9000\n9001,8999 -> 9001,9001").unwrap();
+        let Points::Dense { grid, left, top } = vent_map(&segments, false).points else { panic!("sparse map") };
+
+        assert_eq!((grid.width(), grid.height(), left, top), (4, 3, 9000, 8999));
+        assert_eq!(Day05::part1(&segments), Some(1.into()));
+    }
+
+    #[test]
+    fn point_off_the_map_is_reported() {
+        let error = Day05::parse("0,9 -> 5,9\n8,0 -> 0,10000").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 10, "10000"));
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day05>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day05>(seed, &GenOptions::default());
+        }
+
+        let wide = GenOptions { range: Some(3000), ..GenOptions::default() };
+        aoc::assert_generated::<Day05>(0, &wide);
+    }
+}
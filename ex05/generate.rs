@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day05;
+
+// Horizontal, vertical and diagonal lines of vents anywhere in a square of
+// the given range, like the real input
+impl Generator for Day05 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(500).max(1);
+        let range = options.range.unwrap_or(1000).clamp(1, crate::MAX_COORDINATE + 1) as i64;
+
+        let mut segments = Vec::<[i64; 4]>::new();
+
+        for _ in 0..size {
+            let (x1, y1) = (rng.range(0..range as u64) as i64, rng.range(0..range as u64) as i64);
+            let (x2, y2) = match rng.range(0..3) {
+                0 => (rng.range(0..range as u64) as i64, y1),
+                1 => (x1, rng.range(0..range as u64) as i64),
+                _ => {
+                    // as far as we can go in a random diagonal direction
+                    let dx = *rng.pick(&[-1, 1]);
+                    let dy = *rng.pick(&[-1, 1]);
+                    let room_x = if dx > 0 { range - 1 - x1 } else { x1 };
+                    let room_y = if dy > 0 { range - 1 - y1 } else { y1 };
+                    let length = rng.range(0..room_x.min(room_y) as u64 + 1) as i64;
+
+                    (x1 + dx * length, y1 + dy * length)
+                },
+            };
+
+            segments.push([x1, y1, x2, y2]);
+        }
+
+        let input: Vec::<String> = segments
+            .iter()
+            .map(|[x1, y1, x2, y2]| format!("{},{} -> {},{}", x1, y1, x2, y2))
+            .collect();
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(overlaps(&segments, false).into()),
+            part2: Some(overlaps(&segments, true).into()),
+        }
+    }
+}
+
+// Counts the points covered more than once by walking along every line a
+// step at a time, keeping the counts in a HashMap
+fn overlaps(segments: &[[i64; 4]], diagonals: bool) -> usize {
+    let mut counts = HashMap::<(i64, i64), u32>::new();
+
+    for [x1, y1, x2, y2] in segments {
+        if x1 != x2 && y1 != y2 && !diagonals {
+            continue
+        }
+
+        let (dx, dy) = ((x2 - x1).signum(), (y2 - y1).signum());
+        let steps = (x2 - x1).abs().max((y2 - y1).abs());
+
+        for step in 0..=steps {
+            *counts.entry((x1 + dx * step, y1 + dy * step)).or_insert(0) += 1;
+        }
+    }
+
+    counts.values().filter(|count| **count > 1).count()
+}
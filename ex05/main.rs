@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex05::Day05;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day05>(FILEPATH)
+}
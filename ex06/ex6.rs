@@ -1,21 +1,20 @@
-use std::fs;
-use std::io::Error;
+use aoc::{parser, Answer, Frames, ParseError, Part, Solution, Variant};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
 
 // This returns an array of the number of fish with 0 -> 8 days left until they
 // birth a new fish.
-fn get_file_input() -> Result<[u128; 9], Error> {
-    let input_str = fs::read_to_string(FILEPATH)?;
+fn get_file_input(input: &str) -> Result<[u128; 9], ParseError> {
+    let mut bucket_array: [u128; 9] = [0; 9];
 
-    let input_vec: Vec::<usize> = input_str
-        .split(",")
-        .map(|s| s.parse().expect("Failed to convert &str to u32"))
-        .collect();
+    // The input is a single line, so any errors are on the first line
+    let line = aoc::input::single_line(input)?;
 
-    let mut bucket_array: [u128; 9] = [0; 9];
+    let expected = "a timer between 0 and 8";
+    let timer = parser::verify(parser::integer::<usize>(expected), expected, |i| *i < bucket_array.len());
+    let timers = parser::parse_line(0, line, parser::separated(timer, parser::padded(parser::literal(","))))?;
 
-    for i in input_vec {
+    for i in timers {
         bucket_array[i] += 1
     }
 
@@ -32,20 +31,181 @@ fn update_fish_number(fish_array: &mut [u128; 9]) {
     fish_array[8] = number_of_new_fish;
 }
 
-fn main() -> Result<(), Error> {
-    let mut fish_array = get_file_input()?;
+// Number of fish after the given number of days
+fn count_fish(fish_array: &[u128; 9], days: u32) -> u128 {
+    let mut fish_array = *fish_array;
 
-    for i in 0..256 {
-        if i == 80 {
-            let sum: u128 = fish_array.iter().sum();
-            println!("Number of fish after 80 days = {}", sum);
-        }
-        
+    for day in 1..=days {
         update_fish_number(&mut fish_array);
+        aoc::trace::event(|| format!("day {}: {} fish", day, fish_array.iter().sum::<u128>()));
+    }
+
+    fish_array.iter().sum()
+}
+
+// The straightforward way, keeping track of every fish on its own, to check
+// the buckets with. The number of fish grows exponentially, so this is only
+// any good for part 1.
+fn simulate_every_fish(fish_array: &[u128; 9], days: u32) -> u128 {
+    let mut fish = Vec::<u8>::new();
+    for (timer, count) in fish_array.iter().enumerate() {
+        fish.extend(std::iter::repeat_n(timer as u8, *count as usize));
+    }
+
+    for _ in 0..days {
+        let new_fish = fish.iter().filter(|timer| **timer == 0).count();
+
+        for timer in fish.iter_mut() {
+            *timer = if *timer == 0 { 6 } else { *timer - 1 };
+        }
+        fish.extend(std::iter::repeat_n(8, new_fish));
+    }
+
+    fish.len() as u128
+}
+
+// The animation follows the fish for as long as part 2 does
+const ANIMATION_DAYS: u32 = 256;
+const BAR_WIDTH: usize = 50;
+
+// Draws each timer bucket as a bar. The counts grow exponentially so the bars
+// are on a log scale, otherwise all but the biggest would be invisible.
+fn draw_buckets(fish_array: &[u128; 9], day: u32) -> String {
+    let total: u128 = fish_array.iter().sum();
+    let max_log = (*fish_array.iter().max().unwrap_or(&0) as f64 + 1.0).ln();
+
+    let mut frame = format!("Day {}: {} fish\n\n", day, total);
+
+    for (timer, count) in fish_array.iter().enumerate() {
+        let log = (*count as f64 + 1.0).ln();
+        let width = if max_log > 0.0 { (log / max_log * BAR_WIDTH as f64).round() as usize } else { 0 };
+
+        // The fish about to give birth and the newborns stand out from the rest
+        let colour = match timer {
+            0 => [255, 150, 50],
+            7 | 8 => [90, 220, 120],
+            _ => [80, 140, 230],
+        };
+
+        // padding the bar ourselves, as format! would count the escape codes
+        frame.push_str(&format!(
+            "timer {}: {}{} {}\n",
+            timer, aoc::paint(&"#".repeat(width), colour), " ".repeat(BAR_WIDTH - width), count
+        ));
+    }
+
+    frame
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = [u128; 9];
+
+    fn parse(input: &str) -> Result<[u128; 9], ParseError> {
+        get_file_input(input)
+    }
+
+    fn part1(fish_array: &[u128; 9]) -> Option<Answer> {
+        Some(count_fish(fish_array, 80).into())
+    }
+
+    fn part2(fish_array: &[u128; 9]) -> Option<Answer> {
+        Some(count_fish(fish_array, 256).into())
+    }
+
+    fn variants() -> Vec::<Variant<[u128; 9]>> {
+        vec![
+            Variant {
+                name: "simulating every fish",
+                part: Part::One,
+                solve: |fish_array| Some(simulate_every_fish(fish_array, 80).into()),
+                // Every fish there ends up being, every day
+                work: |fish_array| count_fish(fish_array, 80).saturating_mul(80),
+            },
+        ]
+    }
+
+    fn frames(fish_array: &[u128; 9]) -> Frames {
+        let mut fish_array = *fish_array;
+
+        Box::new((0..=ANIMATION_DAYS).map(move |day| {
+            if day > 0 {
+                update_fish_number(&mut fish_array);
+            }
+            draw_buckets(&fish_array, day)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{property, GenOptions};
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day06>(TEST_INPUT, 5934, 26984457539_u64);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day06>(&input, 5934, 26984457539_u64);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day06>(INPUT, 391888, 1754597645339_u64);
     }
 
-    let sum: u128 = fish_array.iter().sum();
-    println!("Number of fish after 256 days {}", sum);
+    #[test]
+    fn frames_show_every_bucket_each_day() {
+        let fish_array = Day06::parse(TEST_INPUT).unwrap();
+        let frames: Vec::<String> = Day06::frames(&fish_array).collect();
 
-    Ok(())
-}
\ No newline at end of file
+        assert_eq!(frames.len(), 257);
+
+        let day_18 = aoc::strip_ansi(&frames[18]);
+        assert!(day_18.starts_with("Day 18: 26 fish\n"));
+        // the biggest bucket fills the bar, and the rest are on a log scale
+        assert!(day_18.contains(&format!("timer 1: {} 5\n", "#".repeat(50))));
+        assert!(day_18.contains(&format!("timer 5: {:<50} 1\n", "#".repeat(19))));
+    }
+
+    #[test]
+    fn bad_timer_is_reported() {
+        let error = Day06::parse("3,4,9,1").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 5, "9"));
+    }
+
+    #[test]
+    fn buckets_match_simulating_every_fish() {
+        let timers = property::vecs(property::numbers(0..=8), 0..=10);
+        let school = property::pairs(timers, property::numbers(0..=50));
+
+        property::check(2000, &school, |(timers, days)| {
+            let mut buckets = [0; 9];
+            for timer in timers {
+                buckets[*timer as usize] += 1;
+            }
+
+            count_fish(&buckets, *days as u32) == simulate_every_fish(&buckets, *days as u32)
+        });
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day06>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day06>(seed, &GenOptions::default());
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::collections::VecDeque;
+
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day06;
+
+// A school of fish with timers from 1 to 5, like the real input
+impl Generator for Day06 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(300).max(1);
+
+        let timers: Vec::<u64> = (0..size).map(|_| rng.range(1..6)).collect();
+        let input: Vec::<String> = timers.iter().map(|timer| timer.to_string()).collect();
+
+        Generated {
+            input: input.join(","),
+            part1: Some(school_size(&timers, 80).into()),
+            part2: Some(school_size(&timers, 256).into()),
+        }
+    }
+}
+
+// Every day the queue of timers rotates by one, and the fish at the front
+// both go back to 6 and make a new fish at 8
+fn school_size(timers: &[u64], days: u32) -> u128 {
+    let mut queue: VecDeque<u128> = VecDeque::from(vec![0; 9]);
+    for timer in timers {
+        queue[*timer as usize] += 1;
+    }
+
+    for _ in 0..days {
+        let parents = queue.pop_front().unwrap();
+        queue[6] += parents;
+        queue.push_back(parents);
+    }
+
+    queue.iter().sum()
+}
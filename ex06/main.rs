@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex06::Day06;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day06>(FILEPATH)
+}
@@ -1,49 +1,210 @@
-use std::fs;
-use std::io::Error;
+use aoc::{parser, Answer, Details, ParseError, Part, Solution, Variant};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
 
-fn parse_input() -> Result<Vec::<i32>, Error> {
-    let input_str = fs::read_to_string(FILEPATH)?;
+// The input is a single line, so any errors are on the first line
+fn parse_input(input: &str) -> Result<Vec::<i32>, ParseError> {
+    let line = aoc::input::single_line(input)?;
+    let positions = parser::separated(parser::integer("a crab position"), parser::padded(parser::literal(",")));
 
-    let input_vec: Vec::<i32> = input_str
-        .split(",")
-        .map(|x| x.parse().expect("Failed to cast to i32"))
-        .collect();
-
-    Ok(input_vec)
+    parser::parse_line(0, line, positions)
 }
 
-fn calculate_fuel(distance: i32) -> i32 {
+// Worked out in i128, since a crab can be billions of steps away and the cost
+// grows with the square of that
+fn calculate_fuel(distance: i128) -> i128 {
     distance * (distance + 1) / 2
 }
 
-fn main() -> Result<(), Error> {
-    let input_vec = parse_input()?;
+// Finds the cheapest position for all of the crabs to move to, given the fuel
+// it costs to move a crab a distance. Returns the position and the fuel used.
+//
+// Each crab's cost only goes down and then back up as the position moves
+// right, so the total does too. Rather than trying every position (which
+// takes forever when a crab is far away), we binary search for the first
+// position where moving one more to the right doesn't save any fuel.
+fn minimum_fuel(input_vec: &[i32], fuel_cost: fn(i128) -> i128) -> Option<(i64, i128)> {
+    let total_fuel = |position: i64| -> i128 {
+        input_vec
+            .iter()
+            .map(|input| fuel_cost((position - *input as i64).abs() as i128))
+            .sum()
+    };
 
-    let max_value = *input_vec.iter().max().unwrap();
+    let mut low = *input_vec.iter().min()? as i64;
+    let mut high = *input_vec.iter().max()? as i64;
 
-    let mut fuel_vec_1 = Vec::<i32>::new();
-    let mut fuel_vec_2 = Vec::<i32>::new();
-    
-    for i in 0..=max_value {
-        let mut fuel_1 = 0;
-        let mut fuel_2 = 0;
+    while low < high {
+        let middle = low + (high - low) / 2;
+        aoc::trace::event(|| format!("searching {}..={}, trying {}", low, high, middle));
 
-        for input in &input_vec {
-            fuel_1 += (i - input).abs();
-            fuel_2 += calculate_fuel((i - input).abs());
+        if total_fuel(middle + 1) >= total_fuel(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
         }
+    }
+
+    Some((low, total_fuel(low)))
+}
+
+// Tries every position between the crabs, giving the first of the cheapest,
+// to check the binary search with
+fn try_every_position(input_vec: &[i32], fuel_cost: fn(i128) -> i128) -> Option<(i64, i128)> {
+    let min = *input_vec.iter().min()? as i64;
+    let max = *input_vec.iter().max()? as i64;
+
+    (min..=max)
+        .map(|position| {
+            let fuel = input_vec.iter().map(|input| fuel_cost((position - *input as i64).abs() as i128)).sum();
+            (position, fuel)
+        })
+        .min_by_key(|(_, fuel)| *fuel)
+}
+
+// Every crab, for every position between them
+fn every_position_work(input_vec: &[i32]) -> u128 {
+    let min = input_vec.iter().min().map_or(0, |min| *min as i128);
+    let max = input_vec.iter().max().map_or(0, |max| *max as i128);
+
+    input_vec.len() as u128 * (max - min + 1) as u128
+}
+
+fn fuel_details(input_vec: &[i32], fuel_cost: fn(i128) -> i128) -> Details {
+    minimum_fuel(input_vec, fuel_cost)
+        .map_or_else(Vec::new, |(position, _)| vec![("position", position.into())])
+}
+
+// Each step costs one fuel
+fn constant_fuel(distance: i128) -> i128 {
+    distance
+}
 
-        fuel_vec_1.push(fuel_1);
-        fuel_vec_2.push(fuel_2);
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec::<i32>;
+
+    fn parse(input: &str) -> Result<Vec::<i32>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input_vec: &Vec::<i32>) -> Option<Answer> {
+        minimum_fuel(input_vec, constant_fuel).map(|(_, fuel)| fuel.into())
     }
 
-    let min_1: i32 = *fuel_vec_1.iter().min().unwrap();
-    println!("Part 1 - minimum fuel needed = {}", min_1);
+    // Each step costs one more fuel than the last
+    fn part2(input_vec: &Vec::<i32>) -> Option<Answer> {
+        minimum_fuel(input_vec, calculate_fuel).map(|(_, fuel)| fuel.into())
+    }
+
+    fn variants() -> Vec::<Variant<Vec::<i32>>> {
+        vec![
+            Variant {
+                name: "trying every position",
+                part: Part::One,
+                solve: |input_vec| try_every_position(input_vec, constant_fuel).map(|(_, fuel)| fuel.into()),
+                work: |input_vec| every_position_work(input_vec),
+            },
+            Variant {
+                name: "trying every position",
+                part: Part::Two,
+                solve: |input_vec| try_every_position(input_vec, calculate_fuel).map(|(_, fuel)| fuel.into()),
+                work: |input_vec| every_position_work(input_vec),
+            },
+        ]
+    }
+
+    fn part1_details(input_vec: &Vec::<i32>) -> Details {
+        fuel_details(input_vec, constant_fuel)
+    }
+
+    fn part2_details(input_vec: &Vec::<i32>) -> Details {
+        fuel_details(input_vec, calculate_fuel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::{property, GenOptions};
 
-    let min_2: i32 = *fuel_vec_2.iter().min().unwrap();
-    println!("Part 2 - minimum fuel needed = {}", min_2);
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
 
-    Ok(())
-}
\ No newline at end of file
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day07>(TEST_INPUT, 37, 168);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day07>(&input, 37, 168);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day07>(INPUT, 343468, 96086265);
+    }
+
+    #[test]
+    fn bad_position_is_reported() {
+        let error = Day07::parse("16,1,two").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 6, "two"));
+    }
+
+    // The straightforward way, adding up the cost of every step
+    fn brute_force_fuel(distance: i128) -> i128 {
+        (1..=distance).sum()
+    }
+
+    #[test]
+    fn triangular_fuel_matches_adding_up_the_steps() {
+        property::check(5000, &property::numbers(0..=10_000), |distance| {
+            calculate_fuel(*distance as i128) == brute_force_fuel(*distance as i128)
+        });
+    }
+
+    #[test]
+    fn minimum_fuel_matches_trying_every_position() {
+        let positions = property::vecs(property::numbers(0..=100), 0..=20);
+
+        property::check(2000, &positions, |positions| {
+            let positions: Vec::<i32> = positions.iter().map(|p| *p as i32).collect();
+            minimum_fuel(&positions, calculate_fuel) == try_every_position(&positions, brute_force_fuel)
+        });
+    }
+
+    #[test]
+    fn far_away_crabs_dont_overflow() {
+        let positions = vec![0, i32::MAX];
+        let distance = i32::MAX as i128;
+
+        assert_eq!(Day07::part1(&positions), Some(distance.into()));
+        assert_eq!(
+            Day07::part2(&positions),
+            Some((calculate_fuel(distance / 2) + calculate_fuel(distance / 2 + 1)).into())
+        );
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day07>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day07>(seed, &GenOptions::default());
+        }
+    }
+
+    #[test]
+    fn generated_inputs_with_huge_ranges() {
+        for range in [3_000_000_000, usize::MAX] {
+            let options = GenOptions { size: Some(50), range: Some(range), ..GenOptions::default() };
+            aoc::assert_generated::<Day07>(0, &options);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day07;
+
+// Crabs scattered anywhere in the range, with more of them near the start
+// like in the real input. The range is capped so that every position still
+// fits in the i32 the puzzle is parsed into.
+impl Generator for Day07 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(1000).max(1);
+        let range = options.range.unwrap_or(2000).clamp(1, i32::MAX as usize) as u64;
+
+        let positions: Vec::<i64> = (0..size)
+            .map(|_| {
+                let spread = rng.range(1..range + 1);
+                rng.range(0..spread) as i64
+            })
+            .collect();
+
+        let input: Vec::<String> = positions.iter().map(|p| p.to_string()).collect();
+
+        Generated {
+            input: input.join(","),
+            part1: Some(median_fuel(&positions).into()),
+            part2: Some(mean_fuel(&positions).into()),
+        }
+    }
+}
+
+// When each step costs one fuel, the median is the cheapest place to meet
+fn median_fuel(positions: &[i64]) -> i128 {
+    let mut sorted = positions.to_vec();
+    sorted.sort();
+    let median = sorted[sorted.len() / 2];
+
+    positions.iter().map(|p| (p - median).abs() as i128).sum()
+}
+
+// When each step costs one more than the last, the cheapest place is always
+// within half a step of the mean, so it's one of the two whole positions
+// either side of it. Worked out in i128 like the solution, as a few crabs
+// billions of steps apart already cost more fuel than fits in an i64.
+fn mean_fuel(positions: &[i64]) -> i128 {
+    let sum: i64 = positions.iter().sum();
+    let below = sum.div_euclid(positions.len() as i64);
+
+    [below, below + 1]
+        .into_iter()
+        .map(|target| {
+            positions
+                .iter()
+                .map(|p| (p - target).abs() as i128)
+                .map(|distance| distance * (distance + 1) / 2)
+                .sum::<i128>()
+        })
+        .min()
+        .unwrap()
+}
@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex07::Day07;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day07>(FILEPATH)
+}
@@ -1,7 +1,9 @@
-use std::fs;
-use std::io::Error;
+use aoc::{parser, Answer, ParseError, Solution};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
+
+// The lengths of the codes for 0 to 9, sorted
+const CODE_LENGTHS: [usize; 10] = [2, 3, 4, 5, 5, 5, 6, 6, 6, 7];
 
 // string methods to determine which code is which
 fn contains_str(long_str: &str, short_str: &str) -> bool {
@@ -22,25 +24,71 @@ fn is_same_str(first_str: &str, second_str: &str) -> bool {
     is_same_len(first_str, second_str) && contains_str(first_str, second_str)
 }
 
-struct SevenDisplayInput {
-    string: String,
+#[derive(Debug)]
+pub struct SevenDisplayInput {
+    // The code for each digit, in order from 0 to 9
+    digits: Vec::<String>,
+    numbers: Vec::<String>,
 }
 
 impl SevenDisplayInput {
-    fn create(input_str: &str) -> SevenDisplayInput {
-        SevenDisplayInput {
-            string: input_str[..].to_string()
+    // Works out which code is which digit up front, so that a line with codes
+    // that can't be wired up to a display is a parse error rather than
+    // something we trip over when solving
+    fn create(line_index: usize, input_str: &str) -> Result<SevenDisplayInput, ParseError> {
+        let error = |text: &str, expected: &str| ParseError::new(line_index, input_str, text, expected);
+
+        // Each code and number is a group of different segments from a to g
+        let segments = || {
+            let expected = "a group of segments from a to g";
+            let from_a_to_g = parser::verify(parser::token(expected), expected, |segments: &&str| {
+                segments.chars().all(|c| ('a'..='g').contains(&c))
+            });
+
+            parser::verify(from_a_to_g, "a group of segments with no segment repeated", |segments| {
+                segments.chars().all(|c| segments.matches(c).count() == 1)
+            })
+        };
+
+        let groups = || parser::separated(segments(), parser::literal(" "));
+        let line = parser::separated_pair(groups(), parser::literal(" | "), groups());
+        let (codes, numbers) = parser::parse_line(line_index, input_str, line)?;
+
+        let mut code_lengths: Vec::<usize> = codes.iter().map(|code| code.len()).collect();
+        code_lengths.sort();
+
+        if code_lengths != CODE_LENGTHS {
+            return Err(error(input_str, "one code for each digit from 0 to 9"))
+        }
+
+        if numbers.len() != 4 {
+            return Err(error(input_str, "four numbers after the codes"))
+        }
+
+        let digits = Codes(codes)
+            .digits()
+            .ok_or_else(|| error(input_str, "codes that can be wired up to the digits 0 to 9"))?;
+
+        for number in &numbers {
+            if !digits.iter().any(|code| is_same_str(code, number)) {
+                return Err(error(number, "a number made from one of the codes"))
+            }
         }
+
+        Ok(SevenDisplayInput {
+            digits: digits.into_iter().map(String::from).collect(),
+            numbers: numbers.into_iter().map(String::from).collect(),
+        })
     }
 
     // "public" interface
     pub fn get_unique_length_nums(&self) -> u32 {
         let mut number_unique_length_chars = 0;
-        for num in self.numbers() {
-            if is_same_len(num, self.code_1()) || 
-                is_same_len(num, self.code_4()) ||
-                is_same_len(num, self.code_7()) ||
-                is_same_len(num, self.code_8()) {
+        for num in &self.numbers {
+            if is_same_len(num, &self.digits[1]) || 
+                is_same_len(num, &self.digits[4]) ||
+                is_same_len(num, &self.digits[7]) ||
+                is_same_len(num, &self.digits[8]) {
                     number_unique_length_chars += 1;
                 }
         }
@@ -49,132 +97,218 @@ impl SevenDisplayInput {
     }
 
     pub fn parse_numbers(&self) -> u32 {
-        // Calculate the codes vec once as opposed to doing it for every iteration
-        // of the nested for loop
-        let codes_vec = self.codes_vec();
-
         let mut total_number: u32 = 0;
 
         for i in 0..=3 {
-            for j in 0..=9 {
-                if is_same_str(codes_vec[j], self.numbers()[3 - i]) {
+            for (j, code) in self.digits.iter().enumerate() {
+                if is_same_str(code, &self.numbers[3 - i]) {
                     total_number += u32::pow(10, i as u32) * (j as u32);
                 }
             }
         }
 
+        aoc::trace::event(|| format!("{} reads {}", self.numbers.join(" "), total_number));
         total_number
     }
+}
 
-    // These are all "private" methods that will be used by the public methods above
-    fn codes(&self) -> Vec::<&str> {
-        self.string[..58].split(" ").collect()
-    }
+// The ten codes on one line, and how to tell which digit each of them is.
+// Each of these gives None when no code fits, which only happens when the
+// codes don't come from a real display.
+struct Codes<'a>(Vec::<&'a str>);
 
-    fn numbers(&self) -> Vec::<&str> {
-        self.string[61..].split(" ").collect()
+impl<'a> Codes<'a> {
+    fn find(&self, predicate: impl Fn(&str) -> bool) -> Option<&'a str> {
+        self.0.iter().copied().find(|x| predicate(x))
     }
 
-    fn code_0(&self) -> &str {
-        self.codes().iter().find(|x| {
+    fn code_0(&self) -> Option<&'a str> {
+        let (code_1, code_4) = (self.code_1()?, self.code_4()?);
+        self.find(|x| {
             x.len() == 6 
-                && !contains_str(x, self.code_4()) 
-                && contains_str(x, self.code_1())
-        }).unwrap()
+                && !contains_str(x, code_4) 
+                && contains_str(x, code_1)
+        })
     }
 
-    fn code_1(&self) -> &str {
-        self.codes().iter().find(|x| x.len() == 2).unwrap()
+    fn code_1(&self) -> Option<&'a str> {
+        self.find(|x| x.len() == 2)
     }
 
-    fn code_2(&self) -> &str {
-        self.codes().iter().find(|x| {
+    fn code_2(&self) -> Option<&'a str> {
+        let (code_3, code_6) = (self.code_3()?, self.code_6()?);
+        self.find(|x| {
             x.len() == 5 
-                && !contains_str(self.code_6(), x) 
-                && !is_same_str(x, self.code_3())
-        }).unwrap()
+                && !contains_str(code_6, x) 
+                && !is_same_str(x, code_3)
+        })
     }
 
-    fn code_3(&self) -> &str {
-        self.codes().iter().find(|x| {
-            x.len() == 5 && contains_str(x, self.code_1())
-        }).unwrap()
+    fn code_3(&self) -> Option<&'a str> {
+        let code_1 = self.code_1()?;
+        self.find(|x| {
+            x.len() == 5 && contains_str(x, code_1)
+        })
     }
 
-    fn code_4(&self) -> &str {
-        self.codes().iter().find(|x| x.len() == 4).unwrap()
+    fn code_4(&self) -> Option<&'a str> {
+        self.find(|x| x.len() == 4)
     }
 
-    fn code_5(&self) -> &str {
-        self.codes().iter().find(|x| {
-            x.len() == 5 && contains_str(self.code_6(), x)
-        }).unwrap()
+    fn code_5(&self) -> Option<&'a str> {
+        let code_6 = self.code_6()?;
+        self.find(|x| {
+            x.len() == 5 && contains_str(code_6, x)
+        })
     }
 
-    fn code_6(&self) -> &str {
-        self.codes().iter().find(|x| {
-            x.len() == 6 && !contains_str(x, self.code_1())
-        }).unwrap()
+    fn code_6(&self) -> Option<&'a str> {
+        let code_1 = self.code_1()?;
+        self.find(|x| {
+            x.len() == 6 && !contains_str(x, code_1)
+        })
     }
 
-    fn code_7(&self) -> &str {
-        self.codes().iter().find(|x| x.len() == 3).unwrap()
+    fn code_7(&self) -> Option<&'a str> {
+        self.find(|x| x.len() == 3)
     }
 
-    fn code_8(&self) -> &str {
-        self.codes().iter().find(|x| x.len() == 7).unwrap()
+    fn code_8(&self) -> Option<&'a str> {
+        self.find(|x| x.len() == 7)
     }
 
-    fn code_9(&self) -> &str {
-        self.codes().iter().find(|x| {
-            x.len() == 6 && contains_str(x, self.code_4())
-        }).unwrap()
+    fn code_9(&self) -> Option<&'a str> {
+        let code_4 = self.code_4()?;
+        self.find(|x| {
+            x.len() == 6 && contains_str(x, code_4)
+        })
     }
 
-    // We only call this function once per line segment, so it is okay.
-    //
-    // However we could make this faster by iterating through the codes just once
-    // and filling in a vec. This is currently slow because we iterate through
-    // self.codes() once for each number, as opposed to just once.
-    fn codes_vec(&self) -> Vec::<&str> {
-        return vec![
-            self.code_0(),
-            self.code_1(),
-            self.code_2(),
-            self.code_3(),
-            self.code_4(),
-            self.code_5(),
-            self.code_6(),
-            self.code_7(),
-            self.code_8(),
-            self.code_9(),
-        ]
+    // The code for each digit from 0 to 9. Codes that don't come from a real
+    // display can make two digits pick the same code, so we check they're all
+    // different.
+    fn digits(&self) -> Option<Vec::<&'a str>> {
+        let digits = vec![
+            self.code_0()?,
+            self.code_1()?,
+            self.code_2()?,
+            self.code_3()?,
+            self.code_4()?,
+            self.code_5()?,
+            self.code_6()?,
+            self.code_7()?,
+            self.code_8()?,
+            self.code_9()?,
+        ];
+
+        let all_different = digits
+            .iter()
+            .enumerate()
+            .all(|(i, a)| digits[i + 1..].iter().all(|b| !is_same_str(a, b)));
+
+        all_different.then_some(digits)
     }
 }
 
-fn parse_input() -> Result<Vec::<SevenDisplayInput>, Error> {
-    let input_str = fs::read_to_string(FILEPATH)?;
+fn parse_input(input: &str) -> Result<Vec::<SevenDisplayInput>, ParseError> {
+    aoc::input::lines(input)
+        .map(|(i, x)| SevenDisplayInput::create(i, x))
+        .collect()
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec::<SevenDisplayInput>;
+
+    fn parse(input: &str) -> Result<Vec::<SevenDisplayInput>, ParseError> {
+        parse_input(input)
+    }
+
+    // Number of 1s, 4s, 7s and 8s in the output values
+    fn part1(input_vec: &Vec::<SevenDisplayInput>) -> Option<Answer> {
+        let number_unique_length_chars: u32 = input_vec
+            .iter()
+            .map(|string| string.get_unique_length_nums())
+            .sum();
+
+        Some(number_unique_length_chars.into())
+    }
 
-    let input_vec: Vec::<SevenDisplayInput> = input_str
-        .split("\n")
-        .map(|x| SevenDisplayInput::create(x))
-        .collect();
+    // Total sum of the decoded output values
+    fn part2(input_vec: &Vec::<SevenDisplayInput>) -> Option<Answer> {
+        let total_sum: u32 = input_vec
+            .iter()
+            .map(|string| string.parse_numbers())
+            .sum();
 
-    Ok(input_vec)
+        Some(total_sum.into())
+    }
 }
 
-fn main() -> Result<(), Error> {
-    let input_vec = parse_input()?;
-    let mut number_unique_length_chars = 0;
-    let mut total_sum = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day08>(TEST_INPUT, 26, 61229);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day08>(&input, 26, 61229);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day08>(INPUT, 278, 986179);
+    }
+
+    #[test]
+    fn missing_code_is_reported() {
+        let line = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd | fdgacbe cefdb cefbgd gcbe";
+        let error = Day08::parse(line).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
 
-    for string in input_vec {
-        number_unique_length_chars += string.get_unique_length_nums();
-        total_sum += string.parse_numbers();
+    #[test]
+    fn bad_segment_is_reported() {
+        let line = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdx cefbgd gcbe";
+        let error = Day08::parse(line).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (70, "cefdx"));
     }
 
-    println!("Part 1\nNumber of 1s, 4s, 7s, 8s = {}\n", number_unique_length_chars);
-    println!("Part 2\nTotal sum of seven display inputs = {}\n", total_sum);
-    
-    Ok(())
-}
\ No newline at end of file
+    #[test]
+    fn codes_that_cant_be_wired_up_are_reported() {
+        // Every code has the right length, but there's no way to tell 0, 6
+        // and 9 apart
+        let line = "ab abc abcd abcde abcdf abcdg abcdef abcdeg abcdfg abcdefg | ab ab ab ab";
+        let error = Day08::parse(line).unwrap_err();
+        assert_eq!(error.expected, "codes that can be wired up to the digits 0 to 9");
+    }
+
+    #[test]
+    fn repeated_segments_are_reported() {
+        let line = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edd | fdgacbe cefdb cefbgd gcbe";
+        let error = Day08::parse(line).unwrap_err();
+        assert_eq!(error.text, "edd");
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day08>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day08>(seed, &GenOptions::default());
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::Day08;
+
+// The segments lit up for each digit on a correctly wired display
+const DIGIT_SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+// Each display has its wires crossed in a random way, and shows a random
+// four digit number. We know the digits we picked, so the answers come for
+// free.
+impl Generator for Day08 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(200).max(1);
+
+        let mut unique_lengths = 0;
+        let mut total = 0;
+        let mut input = Vec::<String>::new();
+
+        for _ in 0..size {
+            let mut wiring: Vec::<char> = ('a'..='g').collect();
+            rng.shuffle(&mut wiring);
+
+            let mut codes: Vec::<String> = (0..10).map(|digit| scramble(rng, &wiring, digit)).collect();
+            let digits: Vec::<usize> = (0..4).map(|_| rng.index(10)).collect();
+            let outputs: Vec::<String> = digits.iter().map(|digit| scramble(rng, &wiring, *digit)).collect();
+            rng.shuffle(&mut codes);
+
+            unique_lengths += digits.iter().filter(|digit| [1, 4, 7, 8].contains(*digit)).count();
+            total += digits.iter().fold(0, |number, digit| number * 10 + digit);
+
+            input.push(format!("{} | {}", codes.join(" "), outputs.join(" ")));
+        }
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(unique_lengths.into()),
+            part2: Some(total.into()),
+        }
+    }
+}
+
+// The segments for a digit once they've gone through the crossed wires, in a
+// random order
+fn scramble(rng: &mut Rng, wiring: &[char], digit: usize) -> String {
+    let mut segments: Vec::<char> = DIGIT_SEGMENTS[digit]
+        .chars()
+        .map(|c| wiring[(c as u8 - b'a') as usize])
+        .collect();
+
+    rng.shuffle(&mut segments);
+    segments.into_iter().collect()
+}
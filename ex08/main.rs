@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex08::Day08;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day08>(FILEPATH)
+}
@@ -1,52 +1,18 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use aoc::{Answer, Colour, Detail, Details, Grid, Image, ParseError, Solution};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
 
-fn get_file_input() -> Vec::<Vec::<u32>> {
-    let file = File::open(FILEPATH).unwrap();
-    let reader = BufReader::new(file);
-
-    let mut map: Vec::<Vec::<u32>> = Vec::new();
-
-    const RADIX: u32 = 10;
-
-    for line in reader.lines() {
-        let heights: Vec::<u32> = line.unwrap()
-            .chars()
-            .map(|x| x.to_digit(RADIX).unwrap())
-            .collect();
-
-        // This parsing of the input file means we must index map as map[y][x],
-        // because the first index gives us a horizontal line (at fixed y) and
-        // then the second index gives us a point within that line.
-        map.push(heights);
-    }
-
-    map
+fn get_file_input(input: &str) -> Result<Grid::<u32>, ParseError> {
+    aoc::input::digit_grid(input, "height")
 }
 
-fn calculate_if_minima(
-    map: &Vec::<Vec::<u32>>, x: usize, y: usize, map_width: usize, map_height: usize
-) -> bool {
-    // max height on the map is 9, so if we are on an edge we need the fourth
-    // comparison point to definitely be higher than the third
-    let mut comparison_points: [u32; 4] = [10; 4];
-
-    // We must make sure we do not compare to the left if we are at the left
-    // edge, etc.
-    if x > 0                { comparison_points[0] = map[y][x - 1]; }
-    if x < map_width - 1    { comparison_points[1] = map[y][x + 1]; }
-    if y > 0                { comparison_points[2] = map[y - 1][x]; }
-    if y < map_height - 1   { comparison_points[3] = map[y + 1][x]; }
-
-    // Only return true if all four comparison points are greater than map[y][x]
-    comparison_points.iter().all(|p| p > &map[y][x])
+// A point is a minimum if all of its neighbours are higher. Points on the
+// edge of the map just have fewer neighbours to compare against.
+fn calculate_if_minima(map: &Grid::<u32>, x: usize, y: usize) -> bool {
+    map.neighbors4(x, y).all(|p| map[p] > map[(x, y)])
 }
 
-fn fill_basin_vec(
-    basin_vec: &mut Vec::<(usize, usize)>, map: &Vec::<Vec::<u32>>, x: usize, y: usize, map_width: usize, map_height: usize
-) {
+fn fill_basin_vec(basin_vec: &mut Vec::<(usize, usize)>, map: &Grid::<u32>, x: usize, y: usize) {
     // We "explore" the area around a basin point. If we have discovered it is
     // already a basin point, stop as we definitely know it has already been
     // explored
@@ -56,10 +22,9 @@ fn fill_basin_vec(
     // minimum, or satisfies one of the below conditions.
     basin_vec.push((x, y));
 
-    // Explore the next point if we are not at the edge, the next point is
-    // higher than the current point, and the next point is lower than 9.
-    // We have to explore in four directions (left, down, up, right) for each
-    // point within the basin.
+    // Explore the next point if the next point is higher than the current
+    // point, and the next point is lower than 9. We have to explore in all
+    // four directions for each point within the basin.
     //
     // This is a bit annoying because if a point is not part of a basin it can
     // be explored multiple times.
@@ -70,17 +35,12 @@ fn fill_basin_vec(
     // will be explored twice. We could possibly mark points as "explored" in
     // a better way than checking if they are in the basin, but this is even
     // more overhead which doesn't seem too neccesary.
-    if x > 0 && map[y][x - 1] > map[y][x] && map[y][x - 1] < 9 { 
-        fill_basin_vec(basin_vec, map, x - 1, y, map_width, map_height);
-    }
-    if y > 0 && map[y - 1][x] > map[y][x] && map[y - 1][x] < 9 { 
-        fill_basin_vec(basin_vec, map, x, y - 1, map_width, map_height);
-    }
-    if x < map_width - 1 && map[y][x + 1] > map[y][x] && map[y][x + 1] < 9 { 
-        fill_basin_vec(basin_vec, map, x + 1, y, map_width, map_height);
-    }
-    if y < map_height - 1 && map[y + 1][x] > map[y][x] && map[y + 1][x] < 9 { 
-        fill_basin_vec(basin_vec, map, x, y + 1, map_width, map_height);
+    for (next_x, next_y) in map.neighbors4(x, y) {
+        let next = map[(next_x, next_y)];
+
+        if next > map[(x, y)] && next < 9 {
+            fill_basin_vec(basin_vec, map, next_x, next_y);
+        }
     }
 }
 
@@ -98,40 +58,171 @@ fn update_largest_basins(largest_basins: &mut [usize], basin_size: usize) {
     }
 }
 
-fn main() {
-    let map = get_file_input();
-    let map_height = map.len() as usize;
-    let map_width = map[0].len() as usize;
+// Finds the (x, y) of every low point on the map
+fn find_minima(map: &Grid::<u32>) -> Vec::<(usize, usize)> {
+    map.points()
+        .filter(|(x, y)| calculate_if_minima(map, *x, *y))
+        .collect()
+}
 
-    // The sum of all of the basin heights + the number of basins
-    let mut risk_sum = 0;
+// The points in each basin, one basin per low point
+fn find_basins(map: &Grid::<u32>) -> Vec::<Vec::<(usize, usize)>> {
+    find_minima(map)
+        .into_iter()
+        .map(|(x, y)| {
+            let mut basin_vec = Vec::<(usize, usize)>::new();
+            fill_basin_vec(&mut basin_vec, map, x, y);
+
+            aoc::trace::event(|| format!("basin at ({}, {}) has {} points", x, y, basin_vec.len()));
+            basin_vec
+        })
+        .collect()
+}
 
-    // The three largest basins
+fn find_largest_basins(map: &Grid::<u32>) -> [usize; 3] {
     let mut largest_basins: [usize; 3] = [0; 3];
 
-    for y in 0..map_height {
-        for x in 0..map_width {
-            // Passing in the map width and map height here looks a bit funny,
-            // but I don't want to calculate the lengths of the map vec each
-            // time we lookup whether a minima / the basin size.
-            if calculate_if_minima(&map, x, y, map_width, map_height) {
-                risk_sum += map[y][x] + 1;
-                
-                let mut basin_vec = Vec::<(usize, usize)>::new();
-                fill_basin_vec(&mut basin_vec, &map, x, y, map_width, map_height);
-
-                let basin_size = basin_vec.len();
-                update_largest_basins(&mut largest_basins, basin_size);
-            }
+    for basin in find_basins(map) {
+        update_largest_basins(&mut largest_basins, basin.len());
+    }
+
+    largest_basins
+}
+
+// Muted colours for the ordinary basins, which are cycled through, and
+// bright ones for the three largest
+const BASIN_COLOURS: [Colour; 6] = [
+    [70, 110, 150], [90, 140, 100], [130, 100, 150], [150, 120, 80], [80, 130, 140], [140, 90, 100],
+];
+const LARGEST_BASIN_COLOURS: [Colour; 3] = [[255, 80, 60], [255, 200, 40], [60, 220, 255]];
+
+// Each basin gets its own colour, shaded so that the low points are the
+// brightest, and the ridges of 9s between them are left grey
+fn draw_basins(map: &Grid::<u32>) -> Image {
+    let basins = find_basins(map);
+
+    // The indices of the basins from largest to smallest
+    let mut by_size: Vec::<usize> = (0..basins.len()).collect();
+    by_size.sort_by_key(|i| std::cmp::Reverse(basins[*i].len()));
+
+    let mut colours = Grid::new(map.width(), map.height(), None);
+    for (rank, i) in by_size.into_iter().enumerate() {
+        let colour = LARGEST_BASIN_COLOURS
+            .get(rank)
+            .copied()
+            .unwrap_or(BASIN_COLOURS[i % BASIN_COLOURS.len()]);
+
+        for point in &basins[i] {
+            colours[*point] = Some(colour);
         }
     }
 
-    println!("Part 1");
-    println!("Risk sum of map = {}", risk_sum);
-    println!("");
+    let mut image = Grid::new(map.width(), map.height(), [0; 3]);
+    for point in map.points() {
+        let height = map[point];
+
+        image[point] = match colours[point] {
+            Some(colour) => aoc::shade(colour, 1.0 - height as f64 / 15.0),
+            None => [40 + 8 * height as u8; 3],
+        };
+    }
+
+    Image::new(image)
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Grid::<u32>;
+
+    fn parse(input: &str) -> Result<Grid::<u32>, ParseError> {
+        get_file_input(input)
+    }
+
+    // The sum of all of the basin heights + the number of basins
+    fn part1(map: &Grid::<u32>) -> Option<Answer> {
+        let risk_sum: u32 = find_minima(map)
+            .into_iter()
+            .map(|point| map[point] + 1)
+            .sum();
+
+        Some(risk_sum.into())
+    }
+
+    // The sizes of the three largest basins multiplied together
+    fn part2(map: &Grid::<u32>) -> Option<Answer> {
+        let largest_basins = find_largest_basins(map);
+
+        Some((largest_basins[0] * largest_basins[1] * largest_basins[2]).into())
+    }
+
+    fn part1_details(map: &Grid::<u32>) -> Details {
+        vec![("low_points", find_minima(map).len().into())]
+    }
+
+    fn part2_details(map: &Grid::<u32>) -> Details {
+        let largest_basins = find_largest_basins(map);
 
-    println!("Part 2");
-    println!("Largest basins contain the following number of points: {:?}", largest_basins);
-    println!("Multiplied, this gives {:?}", largest_basins[0] * largest_basins[1] * largest_basins[2]);
-    println!("");
-}
\ No newline at end of file
+        vec![("largest_basins", Detail::List(largest_basins.map(Answer::from).to_vec()))]
+    }
+
+    fn render(map: &Grid::<u32>, _step: Option<u32>) -> Option<Image> {
+        Some(draw_basins(map).scaled(4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day09>(TEST_INPUT, 15, 1134);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day09>(&input, 15, 1134);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day09>(INPUT, 631, 821560);
+    }
+
+    #[test]
+    fn largest_basins_are_highlighted() {
+        let map = Day09::parse(TEST_INPUT).unwrap();
+        let image = draw_basins(&map);
+
+        // The low point of the top right basin, which is one of the largest
+        assert_eq!(image.pixel(9, 0), Some(LARGEST_BASIN_COLOURS[1]));
+        // The low point of the top left basin, which is the smallest
+        assert_eq!(image.pixel(1, 0), Some(aoc::shade(BASIN_COLOURS[0], 1.0 - 1.0 / 15.0)));
+        // A ridge between basins
+        assert_eq!(image.pixel(2, 0), Some([112; 3]));
+    }
+
+    #[test]
+    fn ragged_map_is_reported() {
+        let error = Day09::parse("2199943210\n398789921").unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (2, "a row of 10 heights"));
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day09>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day09>(seed, &GenOptions::default());
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::{HashSet, VecDeque};
+
+use aoc::{GenOptions, Generated, Generator, Grid, Rng};
+
+use crate::Day09;
+
+// A heightmap of random heights, with extra 9s so that it's split up into
+// basins rather than being one big one
+impl Generator for Day09 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let width = options.width.unwrap_or(100).max(1);
+        let height = options.height.unwrap_or(100).max(1);
+
+        let mut map = Grid::new(width, height, 0);
+        for cell in map.iter_mut() {
+            *cell = if rng.chance(0.3) { 9 } else { rng.range(0..10) as u32 };
+        }
+
+        let input: Vec::<String> = map
+            .rows()
+            .map(|row| row.iter().map(|height| height.to_string()).collect())
+            .collect();
+
+        let (risk, basins) = low_points_and_basins(&map);
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(risk.into()),
+            part2: basins.map(|product| product.into()),
+        }
+    }
+}
+
+// The total risk of the low points, and the product of the three largest
+// basins if there are three of them. Each basin is found with a breadth first
+// search from its low point, flowing up to any higher point that isn't a 9.
+fn low_points_and_basins(map: &Grid::<u32>) -> (u32, Option<usize>) {
+    let low_points: Vec::<(usize, usize)> = map
+        .points()
+        .filter(|point| map.neighbors4(point.0, point.1).all(|n| map[n] > map[*point]))
+        .collect();
+
+    let risk = low_points.iter().map(|point| map[*point] + 1).sum();
+
+    let mut sizes: Vec::<usize> = low_points
+        .iter()
+        .map(|low_point| {
+            let mut basin = HashSet::from([*low_point]);
+            let mut queue = VecDeque::from([*low_point]);
+
+            while let Some(point) = queue.pop_front() {
+                for next in map.neighbors4(point.0, point.1) {
+                    if map[next] > map[point] && map[next] < 9 && basin.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            basin.len()
+        })
+        .collect();
+
+    sizes.sort_by(|a, b| b.cmp(a));
+    let basins = (sizes.len() >= 3).then(|| sizes[..3].iter().product());
+
+    (risk, basins)
+}
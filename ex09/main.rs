@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex09::Day09;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day09>(FILEPATH)
+}
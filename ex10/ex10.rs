@@ -1,28 +1,14 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use aoc::{Answer, ParseError, Solution};
 
-const FILEPATH: &str = "input.txt";
+mod generate;
 
 const OPEN_BRACKETS: [char; 4] = ['(', '[', '{', '<'];
 const CLOSE_BRACKETS: [char; 4] = [')', ']', '}', '>'];
 
-const ILLEGAL_SCORES: [u32; 4] = [3, 57, 1197, 25137];
+const ILLEGAL_SCORES: [u64; 4] = [3, 57, 1197, 25137];
 const CLOSING_CHAR_ADDITIONS: [u64; 4] = [1, 2, 3, 4];
 
-fn get_file_input() -> Vec::<String> {
-    let file = File::open(FILEPATH).unwrap();
-    let reader = BufReader::new(file);
-
-    let mut input_file = Vec::<String>::new();
-
-    for line in reader.lines() {
-        input_file.push(line.unwrap());
-    }
-
-    input_file
-}
-
-fn update_illegal_score(char: char) -> u32 {
+fn update_illegal_score(char: char) -> u64 {
     if let Some(index) = CLOSE_BRACKETS.iter().position(|c| *c == char) {
         return ILLEGAL_SCORES[index]
     }
@@ -30,53 +16,167 @@ fn update_illegal_score(char: char) -> u32 {
     panic!("Trying to update illegal score for an invalid character!")
 }
 
-fn generate_closing_char_score(string: String) -> u64 {
+// The score grows five times over for every character, so anything more than
+// 28 characters long can overflow, which gives None
+fn generate_closing_char_score(string: String) -> Option<u64> {
     let mut closing_char_score: u64 = 0;
 
     for char in string.chars() {
-        closing_char_score *= 5;
-
         if let Some(index) = CLOSE_BRACKETS.iter().position(|c| *c == char) {
-            closing_char_score += CLOSING_CHAR_ADDITIONS[index];
+            closing_char_score = closing_char_score
+                .checked_mul(5)?
+                .checked_add(CLOSING_CHAR_ADDITIONS[index])?;
         } else {
             panic!("Trying to update the closing pattern score for invalid character!")
         }
     }
 
-    closing_char_score
+    Some(closing_char_score)
 }
 
-fn main() {
-    let chunks = get_file_input();
+// A line is either corrupted by an illegal closing character, or incomplete
+// and missing the closing pattern
+enum ChunkStatus {
+    Corrupted(char),
+    Incomplete(String),
+}
 
-    let mut illegal_score: u32 = 0;
+fn check_chunk(chunk: &str) -> ChunkStatus {
+    let mut close_pattern = String::from("");
+
+    for char in chunk.chars() {
+        if let Some(index) = OPEN_BRACKETS.iter().position(|c| *c == char) {
+            // we've found another opening bracket, the close_pattern needs to be updated
+            close_pattern.insert(0, CLOSE_BRACKETS[index]);
+        } else if close_pattern.starts_with(char) {
+            // we've found the first element of the close pattern, remove it
+            close_pattern.remove(0);
+        } else  {
+            // Syntax error!
+            aoc::trace::event(|| match close_pattern.chars().next() {
+                Some(expected) => format!("{}: expected {}, but found {} instead", chunk, expected, char),
+                None => format!("{}: found {} with nothing to close", chunk, char),
+            });
+            return ChunkStatus::Corrupted(char)
+        }
+    }
 
-    let mut closing_char_scores = Vec::<u64>::new();
+    aoc::trace::event(|| format!("{}: incomplete, completed by {}", chunk, close_pattern));
+    ChunkStatus::Incomplete(close_pattern)
+}
 
-    'line_loop: for chunk in chunks {
-        let mut close_pattern = String::from("");
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec::<String>;
+
+    fn parse(input: &str) -> Result<Vec::<String>, ParseError> {
+        aoc::input::lines(input)
+            .map(|(i, line)| {
+                let bad_char = line
+                    .char_indices()
+                    .find(|(_, c)| !OPEN_BRACKETS.contains(c) && !CLOSE_BRACKETS.contains(c));
+
+                match bad_char {
+                    Some((pos, c)) => Err(ParseError::new(
+                        i, line, &line[pos..pos + c.len_utf8()], "one of ()[]{}<>"
+                    )),
+                    None => Ok(line.to_string()),
+                }
+            })
+            .collect()
+    }
+
+    // Total score of the first illegal character on each corrupted line
+    fn part1(chunks: &Vec::<String>) -> Option<Answer> {
+        let mut illegal_score: u64 = 0;
 
-        for char in chunk.chars() {
-            if let Some(index) = OPEN_BRACKETS.iter().position(|c| *c == char) {
-                // we've found another opening bracket, the close_pattern needs to be updated
-                close_pattern.insert(0, CLOSE_BRACKETS[index]);
-            } else if close_pattern.chars().nth(0).unwrap() == char {
-                // we've found the first element of the close pattern, remove it
-                close_pattern.remove(0);
-            } else  {
-                // Syntax error!
-                // println!("Expected {}, but found {} instead", &close_pattern[..1], char);
+        for chunk in chunks {
+            if let ChunkStatus::Corrupted(char) = check_chunk(chunk) {
                 illegal_score += update_illegal_score(char);
-                continue 'line_loop;
             }
         }
 
-        closing_char_scores.push(generate_closing_char_score(close_pattern));
+        Some(illegal_score.into())
+    }
+
+    // Median score of the patterns needed to complete the incomplete lines,
+    // which we can't give if any of the scores are too big to work out
+    fn part2(chunks: &Vec::<String>) -> Option<Answer> {
+        let mut closing_char_scores = Vec::<u64>::new();
+
+        for chunk in chunks {
+            if let ChunkStatus::Incomplete(close_pattern) = check_chunk(chunk) {
+                closing_char_scores.push(generate_closing_char_score(close_pattern)?);
+            }
+        }
+
+        closing_char_scores.sort();
+        closing_char_scores
+            .get(closing_char_scores.len() / 2)
+            .map(|score| Answer::from(*score))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
 
-    println!("Part 1: Illegal score = {}", illegal_score);
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
 
-    closing_char_scores.sort();
-    let median_closing_score = closing_char_scores[closing_char_scores.len() / 2];
-    println!("Part 2: Median closing char score = {}", median_closing_score);
-}
\ No newline at end of file
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day10>(TEST_INPUT, 26397, 288957);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day10>(&input, 26397, 288957);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day10>(INPUT, 321237, 2360030859_u64);
+    }
+
+    #[test]
+    fn syntax_errors_are_traced() {
+        let chunks = Day10::parse("{([(<{}[<>[]}>{[]{[(<()>").unwrap();
+        let (_, lines) = aoc::trace::capture(aoc::trace::EVENTS, || Day10::part1(&chunks));
+
+        assert_eq!(lines, vec!["{([(<{}[<>[]}>{[]{[(<()>: expected ], but found } instead"]);
+    }
+
+    #[test]
+    fn bad_character_is_reported() {
+        let error = Day10::parse("[({(<(())[]>[[{[]{<()<>>\n[(()[<>])]a").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 11, "a"));
+    }
+
+    #[test]
+    fn unopened_closing_characters_are_corrupted() {
+        let chunks = vec![String::from(")"), String::from("())")];
+        assert_eq!(Day10::part1(&chunks), Some(6.into()));
+    }
+
+    #[test]
+    fn completions_too_long_to_score_have_no_answer() {
+        assert_eq!(Day10::part2(&vec!["(".repeat(28)]), Some(9313225746154785156_u64.into()));
+        assert_eq!(Day10::part2(&vec!["(".repeat(29)]), None);
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day10>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day10>(seed, &GenOptions::default());
+        }
+    }
+}
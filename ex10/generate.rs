@@ -0,0 +1,88 @@
+use aoc::{GenOptions, Generated, Generator, Rng};
+
+use crate::{Day10, CLOSE_BRACKETS, CLOSING_CHAR_ADDITIONS, ILLEGAL_SCORES, OPEN_BRACKETS};
+
+// How long each line is before it's cut off or corrupted, and how deep the
+// chunks are allowed to nest, which keeps the completion scores in a u64
+const LINE_LENGTH: usize = 100;
+const MAX_DEPTH: usize = 12;
+
+// Lines of randomly nested chunks, each of which is either cut off part of
+// the way through or corrupted by a closing bracket that doesn't match. We
+// build the lines ourselves, so we know the answers as we go.
+impl Generator for Day10 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let size = options.size.unwrap_or(100).max(1);
+
+        // There has to be an odd number of incomplete lines for there to be
+        // a middle score
+        let mut incomplete = size.div_ceil(2);
+        if incomplete.is_multiple_of(2) {
+            incomplete -= 1;
+        }
+
+        let mut is_incomplete: Vec::<bool> = (0..size).map(|i| i < incomplete).collect();
+        rng.shuffle(&mut is_incomplete);
+
+        let mut corrupted_score = 0u64;
+        let mut completion_scores = Vec::<u64>::new();
+        let mut input = Vec::<String>::new();
+
+        for incomplete in is_incomplete {
+            let (mut line, open) = random_chunks(rng);
+
+            if incomplete {
+                completion_scores.push(
+                    open.iter().rev().fold(0, |score, i| score * 5 + CLOSING_CHAR_ADDITIONS[*i])
+                );
+            } else {
+                // Any closing bracket apart from the one we're expecting
+                let expected = *open.last().unwrap();
+                let wrong = (expected + 1 + rng.index(3)) % 4;
+
+                line.push(CLOSE_BRACKETS[wrong]);
+                corrupted_score += ILLEGAL_SCORES[wrong];
+
+                // What comes after the corruption doesn't matter
+                for _ in 0..rng.index(10) {
+                    line.push(*rng.pick(&[OPEN_BRACKETS, CLOSE_BRACKETS].concat()));
+                }
+            }
+
+            input.push(line);
+        }
+
+        completion_scores.sort();
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(corrupted_score.into()),
+            part2: Some(completion_scores[completion_scores.len() / 2].into()),
+        }
+    }
+}
+
+// A line of properly nested chunks that's been cut off, along with the kinds
+// of the chunks that are still open. There's always at least one chunk open.
+fn random_chunks(rng: &mut Rng) -> (String, Vec::<usize>) {
+    let mut line = String::new();
+    let mut open = Vec::<usize>::new();
+
+    for _ in 0..LINE_LENGTH {
+        if !open.is_empty() && (open.len() >= MAX_DEPTH || rng.chance(0.5)) {
+            line.push(CLOSE_BRACKETS[open.pop().unwrap()]);
+        } else {
+            let kind = rng.index(4);
+            line.push(OPEN_BRACKETS[kind]);
+            open.push(kind);
+        }
+    }
+
+    if open.is_empty() {
+        let kind = rng.index(4);
+        line.push(OPEN_BRACKETS[kind]);
+        open.push(kind);
+    }
+
+    (line, open)
+}
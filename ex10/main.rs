@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex10::Day10;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day10>(FILEPATH)
+}
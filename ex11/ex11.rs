@@ -1,151 +1,275 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::iter;
 
-const FILEPATH: &str = "input.txt";
+use aoc::{Answer, Frames, Grid, Image, ParseError, Solution};
 
-const MAP_LEN: usize = 10;
+mod generate;
 
-struct Point {
-    x: usize,
-    y: usize,
+// Not every grid of octopuses ends up flashing all at once, so part 2 gives
+// up after this many steps rather than looping forever
+const MAX_SYNC_STEPS: u32 = 10_000;
+
+// The animation stops when every octopus flashes at once, or after this many
+// steps if they never do
+const MAX_ANIMATION_STEPS: u32 = 1000;
+
+// The longest side of a rendered picture, short of a grid so big that each
+// octopus is already only a pixel wide. Small grids are scaled up to fit, but
+// by no more than MAX_SCALE.
+const MAX_IMAGE_SIZE: usize = 1000;
+const MAX_SCALE: usize = 20;
+
+// The real input is always 10x10, but any rectangle of octopuses works
+fn get_file_input(input: &str) -> Result<Grid::<u32>, ParseError> {
+    aoc::input::digit_grid(input, "energy level")
 }
 
-impl Point {
-    fn x_position(&self) -> Position {
-        if self.x == 0                  { return Position::Start }
-        else if self.x == MAP_LEN - 1   { return Position::End }
-        else                            { return Position::Middle}
-    }
+// Draws the energy levels for the terminal, with the octopuses that have just
+// flashed picked out and the rest glowing brighter as they charge
+fn draw_energy_levels(levels: &Grid::<u32>) -> String {
+    let mut frame = String::new();
 
-    fn y_position(&self) -> Position {
-        if self.y == 0                  { return Position::Start }
-        else if self.y == MAP_LEN - 1   { return Position::End }
-        else                            { return Position::Middle }
+    for row in levels.rows() {
+        for level in row {
+            let text = level.to_string();
+
+            if *level == 0 {
+                frame.push_str(&aoc::highlight(&text, [0; 3], [255, 230, 120]));
+            } else {
+                frame.push_str(&aoc::paint(&text, aoc::heat(*level - 1, 8)));
+            }
+        }
+        frame.push('\n');
     }
-}
 
-enum Position {
-    Start,
-    Middle,
-    End,
+    frame
 }
 
-fn get_file_input() -> Vec::<Vec::<u32>> {
-    let file = File::open(FILEPATH).unwrap();
-    let reader = BufReader::new(file);
+// Flashes the octopus at (x, y), and then any of its neighbours that flash
+// in turn. The octopuses still to be updated are kept in a Vec rather than
+// recursing, as a big grid can set off a chain of flashes far deeper than the
+// stack.
+fn update_adjacent_levels(map: &mut Grid::<u32>, x: usize, y: usize, number_of_flashes: &mut u32) {
+    let mut pending = vec![(x, y)];
 
-    let mut input_file = Vec::<Vec::<u32>>::new();
+    while let Some(point) = pending.pop() {
+        if map[point] == 0 {
+            continue
+        }
 
-    const RADIX: u32 = 10;
+        // An initial point has flashed
+        if map[point] == 10 {
+            map[point] = 0;
+        } else {
+            map[point] += 1;
 
-    for line in reader.lines() {
-        let parsed_line: Vec::<u32> = line.unwrap()
-            .chars()
-            .map(|x| x.to_digit(RADIX).unwrap())
-            .collect();
+            // An adjacent point has flashed
+            if map[point] == 10 {
+                map[point] = 0;
+            }
+        }
 
-        input_file.push(parsed_line);
-    }
+        if map[point] > 0 {
+            continue
+        }
 
-    input_file
+        // A flash triggers adjacent levels to be updated. We don't want to
+        // update the current point because it only triggers adjacent updates,
+        // which neighbors8 already leaves out for us.
+        *number_of_flashes += 1;
+        pending.extend(map.neighbors8(point.0, point.1));
+    }
 }
 
-// Helper method used while debugging to print energy levels in a readable form
-fn print_energy_levels(levels: &Vec::<Vec::<u32>>) {
-    for y in 0..levels.len() {
-        for x in 0..levels[0].len() {
-            print!("{}", levels[y][x]);
+// Runs a single step of the simulation, returning the number of flashes
+fn step(energy_levels: &mut Grid::<u32>) -> u32 {
+    let mut number_of_flashes = 0;
+
+    // We update each of the energy levels by one, and then check the state
+    // of them and see if any of the octopuses need to update their neighbors
+    // and then count the number of flashes per step.
+    //
+    // This involves looping through the energy levels array three times for
+    // per step which is not the best
+    for level in energy_levels.iter_mut() {
+        *level += 1;
+    }
+
+    for (x, y) in energy_levels.points() {
+        if energy_levels[(x, y)] == 10 {
+            let before = number_of_flashes;
+            update_adjacent_levels(energy_levels, x, y, &mut number_of_flashes);
+
+            let cascade = number_of_flashes - before;
+            aoc::trace::event(|| format!("({}, {}) set off a cascade of {} flashes", x, y, cascade));
         }
-        println!("");
     }
 
-    println!("\n");
+    number_of_flashes
 }
 
-fn update_adjacent_levels(map: &mut Vec::<Vec::<u32>>, point: Point, number_of_flashes: &mut u32) {
-    if map[point.y][point.x] == 0 {
-        return
-    }
+pub struct Day11;
 
-    // An initial point has flashed
-    if map[point.y][point.x] == 10 {
-        map[point.y][point.x] = 0;
-    } else {
-        map[point.y][point.x] += 1;
-        
-        // An adjacent point has flashed
-        if map[point.y][point.x] == 10 {
-            map[point.y][point.x] = 0;
-        }
-    }
+impl Solution for Day11 {
+    type Input = Grid::<u32>;
 
-    if map[point.y][point.x] > 0 {
-        return
+    fn parse(input: &str) -> Result<Grid::<u32>, ParseError> {
+        get_file_input(input)
     }
 
-    // A flash triggers adjacent levels to be updated
-    *number_of_flashes += 1;
+    // Number of flashes after 100 updates
+    fn part1(energy_levels: &Grid::<u32>) -> Option<Answer> {
+        let mut energy_levels = energy_levels.clone();
 
-    let x_update_points: Vec::<usize> = match point.x_position() {
-        Position::Start     => vec![point.x, point.x + 1],
-        Position::Middle    => vec![point.x - 1, point.x, point.x + 1],
-        Position::End       => vec![point.x - 1, point.x],
-    };
+        let number_of_flashes: u32 = (1..=100)
+            .map(|step_count| {
+                let flashes = step(&mut energy_levels);
+                aoc::trace::event(|| format!("step {}: {} flashes", step_count, flashes));
+                flashes
+            })
+            .sum();
 
-    let y_update_points: Vec::<usize> = match point.y_position() {
-        Position::Start     => vec![point.y, point.y + 1],
-        Position::Middle    => vec![point.y - 1, point.y, point.y + 1],
-        Position::End       => vec![point.y - 1, point.y],
-    };
+        Some(number_of_flashes.into())
+    }
 
-    for y in &y_update_points {
-        for x in &x_update_points {
-            let next_point = Point { x: *x, y: *y };
+    // Number of updates until every octopus flashes at once
+    fn part2(energy_levels: &Grid::<u32>) -> Option<Answer> {
+        let mut energy_levels = energy_levels.clone();
+        for update_count in 1..=MAX_SYNC_STEPS {
+            let flashes = step(&mut energy_levels);
+            aoc::trace::event(|| format!("step {}: {} flashes", update_count, flashes));
 
-            // we don't want to update the current point because it only trigger
-            // adjacent updates.
-            if (next_point.x, next_point.y) != (point.x, point.y) {
-                update_adjacent_levels(map, next_point, number_of_flashes);
+            if energy_levels.iter().all(|x| *x == 0) {
+                return Some(update_count.into())
             }
         }
+
+        None
     }
-}
 
-fn main() {
-    let mut energy_levels = get_file_input();
+    // One frame per step, until they all flash together
+    fn frames(energy_levels: &Grid::<u32>) -> Frames {
+        let mut energy_levels = energy_levels.clone();
+        let mut total_flashes = 0;
+        let mut all_flashed = false;
 
-    let mut number_of_flashes = 0;
-    let mut update_count = 0;
-
-    loop {
-        // We update each of the energy levels by one, and then check the state
-        // of them and see if any of the octopuses need to update their neighbors
-        // and then count the number of flashes per step.
-        //
-        // This involves looping through the energy levels array three times for
-        // per step which is not the best
-        energy_levels = energy_levels
-            .into_iter()
-            .map(|x| x.into_iter().map(|y| y + 1).collect())
-            .collect();
-
-        for y in 0..energy_levels.len() {
-            for x in 0..energy_levels[0].len() {
-                if energy_levels[y][x] == 10 {
-                    update_adjacent_levels(&mut energy_levels, Point { x, y }, &mut number_of_flashes);
-                }
+        let start = format!("Step 0\n\n{}", draw_energy_levels(&energy_levels));
+
+        let steps = (1..=MAX_ANIMATION_STEPS).map_while(move |step_count| {
+            if all_flashed {
+                return None
             }
-        }
 
-        update_count += 1;
+            let flashes = step(&mut energy_levels);
+            total_flashes += flashes;
+            all_flashed = energy_levels.iter().all(|x| *x == 0);
+
+            Some(format!(
+                "Step {}: {} flashes, {} in total\n\n{}",
+                step_count, flashes, total_flashes, draw_energy_levels(&energy_levels)
+            ))
+        });
+
+        Box::new(iter::once(start).chain(steps))
+    }
 
-        if update_count == 100 {
-            println!("Part 1: Number of flashes after 100 updates = {}", number_of_flashes);
+    // The octopuses after the given number of steps, where the ones that
+    // have just flashed are white and the rest glow brighter as they charge.
+    // Like part 2, this gives up stepping after MAX_SYNC_STEPS.
+    fn render(energy_levels: &Grid::<u32>, step_count: Option<u32>) -> Option<Image> {
+        let mut energy_levels = energy_levels.clone();
+
+        for _ in 0..step_count.unwrap_or(0).min(MAX_SYNC_STEPS) {
+            step(&mut energy_levels);
         }
 
-        if energy_levels.iter().all(|x| x.iter().all(|y| *y == 0)) {
-            println!("Part 2: Number of updates for a total flash = {}", update_count);
-            break;
+        let image = Image::from_grid(&energy_levels, |level| {
+            if *level == 0 { [255; 3] } else { aoc::heat(*level - 1, 8) }
+        });
+
+        let longest_side = energy_levels.width().max(energy_levels.height()).max(1);
+        Some(image.scaled((MAX_IMAGE_SIZE / longest_side).clamp(1, MAX_SCALE)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::GenOptions;
+
+    const TEST_INPUT: &str = include_str!("test_input.txt");
+    const INPUT: &str = include_str!("input.txt");
+
+    #[test]
+    fn example() {
+        aoc::assert_answers::<Day11>(TEST_INPUT, 1656, 195);
+    }
+
+    #[test]
+    fn windows_line_endings_and_trailing_blank_lines() {
+        let input = TEST_INPUT.replace('\n', "\r\n") + "\r\n \n";
+        aoc::assert_answers::<Day11>(&input, 1656, 195);
+    }
+
+    #[test]
+    fn real_input() {
+        aoc::assert_answers::<Day11>(INPUT, 1649, 256);
+    }
+
+    #[test]
+    fn render_shows_the_flashes_at_a_step() {
+        let energy_levels = Day11::parse(TEST_INPUT).unwrap();
+
+        // The example only has its first flashes on step 2, where the top
+        // left octopus is at 8
+        let before = Day11::render(&energy_levels, Some(1)).unwrap();
+        let after = Day11::render(&energy_levels, Some(2)).unwrap();
+
+        assert_eq!((after.width(), after.height()), (200, 200));
+        assert!(before.pixel(10, 10) != Some([255; 3]));
+        assert_eq!(after.pixel(10, 10), Some(aoc::heat(7, 8)));
+        assert_eq!(after.pixel(50, 10), Some([255; 3]));
+    }
+
+    #[test]
+    fn renders_stay_a_bounded_size() {
+        let energy_levels = Day11::parse(TEST_INPUT).unwrap();
+        assert!(Day11::render(&energy_levels, Some(u32::MAX)).is_some());
+
+        let wide = Day11::render(&Grid::new(2000, 10, 1), None).unwrap();
+        assert_eq!((wide.width(), wide.height()), (2000, 10));
+
+        let tall = Day11::render(&Grid::new(10, 200, 1), None).unwrap();
+        assert_eq!((tall.width(), tall.height()), (50, 1000));
+    }
+
+    #[test]
+    fn frames_run_until_they_all_flash() {
+        let energy_levels = Day11::parse(TEST_INPUT).unwrap();
+        let frames: Vec::<String> = Day11::frames(&energy_levels).collect();
+
+        assert_eq!(frames.len(), 196);
+        assert!(aoc::strip_ansi(&frames[2]).starts_with("Step 2: 35 flashes, 35 in total\n\n8807476555\n"));
+        assert!(aoc::strip_ansi(&frames[195]).ends_with(&"0000000000\n".repeat(10)));
+    }
+
+    #[test]
+    fn grid_must_be_a_rectangle() {
+        let error = Day11::parse("5483143223\n2745854711\n52645").unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (3, "a row of 10 energy levels"));
+    }
+
+    #[test]
+    fn fuzzing() {
+        aoc::assert_fuzzed::<Day11>(&[TEST_INPUT], 2000);
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for seed in 0..5 {
+            aoc::assert_generated::<Day11>(seed, &GenOptions::default());
         }
+
+        let big = GenOptions { width: Some(50), height: Some(40), ..GenOptions::default() };
+        aoc::assert_generated::<Day11>(0, &big);
     }
-}
\ No newline at end of file
+}
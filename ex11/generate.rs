@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+use aoc::{GenOptions, Generated, Generator, Grid, Rng};
+
+use crate::{Day11, MAX_SYNC_STEPS};
+
+// Looking for the step where they all flash together takes too long on big
+// grids, so we only do it for grids up to this many octopuses
+const MAX_SYNC_CELLS: usize = 10_000;
+
+// A grid of octopuses with random energy levels
+impl Generator for Day11 {
+    fn generate(rng: &mut Rng, options: &GenOptions) -> Generated {
+        let width = options.width.unwrap_or(10).max(1);
+        let height = options.height.unwrap_or(10).max(1);
+
+        let mut levels = Grid::new(width, height, 0);
+        for level in levels.iter_mut() {
+            *level = rng.range(0..10) as u32;
+        }
+
+        let input: Vec::<String> = levels
+            .rows()
+            .map(|row| row.iter().map(|level| level.to_string()).collect())
+            .collect();
+
+        let mut flashes = 0;
+        let mut synchronised = None;
+
+        // Like the solution, part 2 has no answer if the octopuses haven't all
+        // flashed together by MAX_SYNC_STEPS
+        let sync_steps = if width * height <= MAX_SYNC_CELLS { MAX_SYNC_STEPS } else { 0 };
+
+        for step_count in 1..=sync_steps.max(100) {
+            let step_flashes = step(&mut levels);
+
+            if step_count <= 100 {
+                flashes += step_flashes;
+            }
+            if step_flashes == width * height && synchronised.is_none() {
+                synchronised = Some(step_count);
+            }
+            if step_count >= 100 && synchronised.is_some() {
+                break
+            }
+        }
+
+        Generated {
+            input: input.join("\n"),
+            part1: Some(flashes.into()),
+            part2: synchronised.map(|step_count| step_count.into()),
+        }
+    }
+}
+
+// Every octopus gains one energy, then the ones over 9 flash, which gives
+// their neighbours one more energy, and so on with a queue until nothing new
+// flashes. The ones that flashed go back to 0.
+fn step(levels: &mut Grid::<u32>) -> usize {
+    let mut flashed = Grid::new(levels.width(), levels.height(), false);
+    let mut queue = VecDeque::<(usize, usize)>::new();
+
+    for point in levels.points() {
+        levels[point] += 1;
+        if levels[point] > 9 {
+            flashed[point] = true;
+            queue.push_back(point);
+        }
+    }
+
+    while let Some(point) = queue.pop_front() {
+        // flashed is the same size as levels, and isn't being changed here
+        let neighbours: Vec::<(usize, usize)> = flashed.neighbors8(point.0, point.1).collect();
+        for neighbour in neighbours {
+            levels[neighbour] += 1;
+
+            if levels[neighbour] > 9 && !flashed[neighbour] {
+                flashed[neighbour] = true;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    for (level, flashed) in levels.iter_mut().zip(flashed.iter()) {
+        if *flashed {
+            *level = 0;
+        }
+    }
+
+    flashed.iter().filter(|flashed| **flashed).count()
+}
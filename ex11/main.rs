@@ -0,0 +1,9 @@
+use std::process::ExitCode;
+
+use ex11::Day11;
+
+const FILEPATH: &str = "input.txt";
+
+fn main() -> ExitCode {
+    aoc::run_file::<Day11>(FILEPATH)
+}